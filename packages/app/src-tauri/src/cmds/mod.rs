@@ -1,4 +1,5 @@
 pub mod locale;
+pub mod scaffold;
 pub mod window;
 pub mod workspace;
 
@@ -11,12 +12,21 @@ macro_rules! command_handler {
 			cmds::locale::read_locale_setting,
 			cmds::locale::write_locale_setting,
 
+			cmds::scaffold::create_pack,
+			cmds::scaffold::create_texture,
+			cmds::scaffold::create_option,
+			cmds::scaffold::create_provider,
+
 			cmds::workspace::list_existing_workspaces,
 			cmds::workspace::check_workspace_name_is_available,
 			cmds::workspace::create_new_workspace,
 			cmds::workspace::open_workspace,
 			cmds::workspace::get_frontend_data_for,
 			cmds::workspace::prompt_add_pack,
+			cmds::workspace::remove_pack,
+			cmds::workspace::reload_pack,
+			cmds::workspace::move_pack,
+			cmds::workspace::set_watching_workspace,
 
 			cmds::window::get_workspace_name
 		]
@@ -0,0 +1,53 @@
+use crate::error::*;
+use ::wiwipaccer_core::scaffold;
+
+/// creates a new pack in dir (which doesn't have to be empty)
+#[tauri::command]
+pub async fn create_pack(dir: String, id: String, name: String) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		scaffold::create_pack(&dir, &id, &name).await?;
+		Ok(())
+	}).await
+}
+
+/// creates a new texture in the pack in pack_dir
+#[tauri::command]
+pub async fn create_texture(
+	pack_dir: String,
+	texture_id: String,
+	name: String
+) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		scaffold::create_texture(&pack_dir, &texture_id, &name).await?;
+		Ok(())
+	}).await
+}
+
+/// creates a new option for a texture in the pack in pack_dir
+#[tauri::command]
+pub async fn create_option(
+	pack_dir: String,
+	texture_id: String,
+	option_id: String,
+	name: String
+) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		scaffold::create_option(&pack_dir, &texture_id, &option_id, &name).await?;
+		Ok(())
+	}).await
+}
+
+/// creates a new provider (copying files from its `files` dir) for an option
+/// in the pack in pack_dir
+#[tauri::command]
+pub async fn create_provider(
+	pack_dir: String,
+	texture_id: String,
+	option_id: String,
+	provider_id: String
+) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		scaffold::create_provider(&pack_dir, &texture_id, &option_id, &provider_id).await?;
+		Ok(())
+	}).await
+}
@@ -1,4 +1,5 @@
 use crate::data::DataTauriState;
+use crate::data::locale::LocaleSetting;
 use crate::data::workspaces::SavedWorkspace;
 use crate::error::*;
 use crate::window::{ self, WindowType };
@@ -51,14 +52,16 @@ pub async fn open_workspace<R: Runtime>(handle: AppHandle<R>, name: String) {
 pub async fn get_frontend_data_for(
 	name: String,
 	mc_version: String,
-	workspaces: WorkspacesTauriState<'_>
+	workspaces: WorkspacesTauriState<'_>,
+	db: DataTauriState<'_>
 ) -> ResultStringErr<::serde_json::Value> {
 	string_error(async {
 		let mc_version = MCVersion::get(&mc_version)?;
+		let locales = LocaleSetting::read_or_default(&db).await?.into_inner();
 		let workspace = workspaces.create_or_open_or_get(&name).await?;
 
 		let lock = workspace.lock().await;
-		let frontend_data = lock.frontend_data(mc_version);
+		let frontend_data = lock.frontend_data(mc_version, &locales);
 		let frontend_data = ::serde_json::to_value(frontend_data)?;
 		drop(lock);
 
@@ -92,3 +95,72 @@ pub async fn prompt_add_pack/* <R: Runtime> */(
 		Ok(())
 	}).await
 }
+
+// TODO fetch state out of window automatically here
+#[tauri::command]
+pub async fn remove_pack(
+	name: String,
+	pack_id: String,
+	workspaces: WorkspacesTauriState<'_>
+) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		let workspace = workspaces.create_or_open_or_get(&name).await?;
+		let mut lock = workspace.lock().await;
+		lock.remove_pack(&pack_id)?;
+		drop(lock);
+
+		Ok(())
+	}).await
+}
+
+/// reads the pack from disk again, keeping its position
+// TODO fetch state out of window automatically here
+#[tauri::command]
+pub async fn reload_pack(
+	name: String,
+	pack_id: String,
+	workspaces: WorkspacesTauriState<'_>
+) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		let workspace = workspaces.create_or_open_or_get(&name).await?;
+		let mut lock = workspace.lock().await;
+		lock.reload_pack(&pack_id).await?;
+		drop(lock);
+
+		Ok(())
+	}).await
+}
+
+/// moves the pack to `index` in the layering order (later packs take priority)
+// TODO fetch state out of window automatically here
+#[tauri::command]
+pub async fn move_pack(
+	name: String,
+	pack_id: String,
+	index: usize,
+	workspaces: WorkspacesTauriState<'_>
+) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		let workspace = workspaces.create_or_open_or_get(&name).await?;
+		let mut lock = workspace.lock().await;
+		lock.move_pack(&pack_id, index)?;
+		drop(lock);
+
+		Ok(())
+	}).await
+}
+
+/// starts or stops reloading packs when their files change, after which
+/// [`PACKS_RELOADED_EVENT`](crate::watch::PACKS_RELOADED_EVENT) is emitted
+// TODO fetch state out of window automatically here
+#[tauri::command]
+pub async fn set_watching_workspace<R: Runtime>(
+	handle: AppHandle<R>,
+	name: String,
+	watching: bool,
+	workspaces: WorkspacesTauriState<'_>
+) -> WrappedTSResult<()> {
+	wrapped_ts_result(async {
+		workspaces.set_watching(handle, &name, watching).await
+	}).await
+}
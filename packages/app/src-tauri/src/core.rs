@@ -1,10 +1,11 @@
 use crate::data::AppDB;
 use crate::data::workspaces::SavedWorkspace;
 use crate::error::*;
+use crate::watch::Watcher;
 use ::hashbrown::HashMap;
 use ::std::ffi::OsStr;
 use ::std::sync::Arc;
-use ::tauri::State;
+use ::tauri::{ AppHandle, Runtime, State };
 use ::tokio::sync::RwLock;
 use ::tokio::sync::Mutex;
 use ::wiwipaccer_core::mc_versions::MCVersionRef;
@@ -23,9 +24,9 @@ pub struct Workspaces {
 	workspaces: RwLock<HashMap<String, Arc<Mutex<WorkspaceWrapper>>>>
 }
 
-#[repr(transparent)]
 pub struct WorkspaceWrapper {
-	workspace: WorkspaceRuntime
+	workspace: WorkspaceRuntime,
+	watcher: Option<Watcher>
 }
 
 pub type WorkspacesTauriState<'h> = State<'h, Workspaces>;
@@ -55,11 +56,11 @@ impl Workspaces {
 		let workspace = if let Some(saved) = saved {
 			let config = saved.into_inner();
 			let workspace = WorkspaceRuntime::from_config_str(&config).await?;
-			Arc::new(Mutex::new(WorkspaceWrapper { workspace }))
+			Arc::new(Mutex::new(WorkspaceWrapper { workspace, watcher: None }))
 		} else {
 			let workspace = WorkspaceRuntime::new(workspace2::nr::Name::new(name.into()));
 			SavedWorkspace::new(workspace.to_config_str()?).write(name, &self.db).await?;
-			Arc::new(Mutex::new(WorkspaceWrapper { workspace }))
+			Arc::new(Mutex::new(WorkspaceWrapper { workspace, watcher: None }))
 		};
 
 		let cloned = Arc::clone(&workspace);
@@ -71,17 +72,76 @@ impl Workspaces {
 
 		Ok(workspace)
 	}
+
+	/// starts or stops reloading packs in a workspace when their files change
+	pub async fn set_watching<R: Runtime>(
+		&self,
+		handle: AppHandle<R>,
+		name: &str,
+		watching: bool
+	) -> Result<()> {
+		let workspace = self.create_or_open_or_get(name).await?;
+
+		let watcher = if watching {
+			Some(Watcher::new(handle, name.into(), &workspace)?)
+		} else {
+			None
+		};
+
+		workspace.lock().await.watcher = watcher;
+
+		Ok(())
+	}
 }
 
 impl WorkspaceWrapper {
 	#[inline]
-	pub fn frontend_data(&self, mc_version: MCVersionRef) -> workspace2::FrontendData {
-		workspace2::FrontendData::new(&self.workspace, mc_version)
+	pub fn frontend_data(&self, mc_version: MCVersionRef, locales: &[String]) -> workspace2::FrontendData {
+		workspace2::FrontendData::new(&self.workspace, mc_version, locales)
 	}
 
 	#[inline]
 	pub async fn add_pack_osstr(&mut self, dir: &OsStr) -> Result<()> {
 		// TODO: save to db, somehow
-		self.workspace.add_pack_with_dir_osstr(dir).await.map_err(Into::into)
+		self.workspace.add_pack_with_dir_osstr(dir).await?;
+		self.packs_changed();
+		Ok(())
+	}
+
+	#[inline]
+	pub fn remove_pack(&mut self, pack_id: &str) -> Result<()> {
+		// TODO: save to db, somehow
+		self.workspace.remove_pack(pack_id)?;
+		self.packs_changed();
+		Ok(())
+	}
+
+	#[inline]
+	pub async fn reload_pack(&mut self, pack_id: &str) -> Result<()> {
+		self.workspace.reload_pack(pack_id).await.map_err(Into::into)
+	}
+
+	#[inline]
+	pub fn move_pack(&mut self, pack_id: &str, index: usize) -> Result<()> {
+		// TODO: save to db, somehow
+		self.workspace.move_pack(pack_id, index).map_err(Into::into)
+	}
+
+	fn packs_changed(&self) {
+		if let Some(watcher) = &self.watcher { watcher.packs_changed() }
+	}
+}
+
+impl AsRef<WorkspaceRuntime> for WorkspaceWrapper {
+	#[inline]
+	fn as_ref(&self) -> &WorkspaceRuntime {
+		&self.workspace
+	}
+}
+
+impl AsMut<WorkspaceRuntime> for WorkspaceWrapper {
+	#[inline]
+	fn as_mut(&mut self) -> &mut WorkspaceRuntime {
+		&mut self.workspace
 	}
 }
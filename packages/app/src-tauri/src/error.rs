@@ -13,6 +13,9 @@ pub enum Error {
 	#[error("non UTF-8 paths are not supported")]
 	NonUtf8Path,
 
+	#[error("{}", .0.to_error_message())]
+	ScaffoldError(::wiwipaccer_core::error::scaffold_err::Scaffold),
+
 	#[error(transparent)]
 	SerdeJsonError(#[from] ::serde_json::Error),
 
@@ -22,10 +25,27 @@ pub enum Error {
 	#[error(transparent)]
 	TauriError(#[from] ::tauri::Error),
 
+	#[error("{}", .0.to_error_message())]
+	WatchError(::wiwipaccer_core::error::watch_err::Watch),
+
 	#[error(transparent)]
 	WorkspaceError(#[from] ::wiwipaccer_core::workspace2::Error)
 }
 
+// only implements `NiceErrorMessage`, so can't use `#[from]`
+impl From<::wiwipaccer_core::error::scaffold_err::Scaffold> for Error {
+	fn from(error: ::wiwipaccer_core::error::scaffold_err::Scaffold) -> Self {
+		Self::ScaffoldError(error)
+	}
+}
+
+// only implements `NiceErrorMessage`, so can't use `#[from]`
+impl From<::wiwipaccer_core::error::watch_err::Watch> for Error {
+	fn from(error: ::wiwipaccer_core::error::watch_err::Watch) -> Self {
+		Self::WatchError(error)
+	}
+}
+
 pub type ResultStringErr<T> = std::result::Result<T, String>;
 #[inline]
 pub async fn string_error<F, T>(future: F) -> ResultStringErr<T>
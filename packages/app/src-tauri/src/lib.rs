@@ -1,5 +1,6 @@
 // TODO: remove when more finished
 #![allow(unused)]
+#![allow(deprecated)]
 
 #![allow(clippy::new_without_default)]
 /*
@@ -10,3 +11,5 @@ pub mod error;
 pub mod rt;
 pub mod window;
 */
+
+pub mod watch;
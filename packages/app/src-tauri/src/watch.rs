@@ -0,0 +1,142 @@
+use ::serde::Serialize;
+use ::std::sync::{ Arc, Weak };
+use ::std::time::Duration;
+use ::tauri::{ async_runtime, AppHandle, Manager as _, Runtime };
+use ::tauri::async_runtime::JoinHandle;
+use ::tokio::select;
+use ::tokio::sync::{ Mutex, Notify };
+use ::wiwipaccer_core::error::watch_err;
+use ::wiwipaccer_core::watch;
+use ::wiwipaccer_core::workspace2::WorkspaceRuntime;
+
+/// emitted (to all windows) after parts of packs in a workspace were reloaded
+/// because their files changed, with [`PacksReloaded`] as the payload
+pub const PACKS_RELOADED_EVENT: &str = "packs-reloaded";
+
+/// how long it has to be quiet for after a change before reloading
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches the source directories of the packs in a workspace (inotify on
+/// linux), reloading the textures, options and providers that changed (see
+/// [`watch::Watcher`]). Stops when dropped.
+pub struct Watcher {
+	packs_changed: Arc<Notify>,
+	task: JoinHandle<()>
+}
+
+#[derive(Clone, Serialize)]
+pub struct PacksReloaded {
+	workspace: String,
+	/// `pack/texture/option/provider` (or less of it) of each reloaded entry
+	reloaded: Vec<String>,
+	/// entries that failed to reload are left as they were
+	errors: Vec<String>
+}
+
+impl Watcher {
+	/// `workspace` can be anything holding a workspace (ex. the app's wrapper
+	/// around one)
+	pub fn new<R, W>(
+		handle: AppHandle<R>,
+		name: String,
+		workspace: &Arc<Mutex<W>>
+	) -> Result<Self, watch_err::Watch>
+	where
+		R: Runtime,
+		W: AsRef<WorkspaceRuntime> + AsMut<WorkspaceRuntime> + Send + 'static
+	{
+		let watcher = watch::Watcher::new(DEBOUNCE)?;
+		let packs_changed = Arc::new(Notify::new());
+
+		let workspace = Arc::downgrade(workspace);
+		let task = async_runtime::spawn(reload_loop(
+			handle,
+			name,
+			workspace,
+			watcher,
+			Arc::clone(&packs_changed)
+		));
+
+		Ok(Self { packs_changed, task })
+	}
+
+	/// to be called after packs are added to or removed from the workspace, so
+	/// their directories start (or stop) being watched
+	#[inline]
+	pub fn packs_changed(&self) {
+		self.packs_changed.notify_one();
+	}
+}
+
+impl Drop for Watcher {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
+
+async fn reload_loop<R, W>(
+	handle: AppHandle<R>,
+	name: String,
+	workspace: Weak<Mutex<W>>,
+	mut watcher: watch::Watcher,
+	packs_changed: Arc<Notify>
+)
+where
+	R: Runtime,
+	W: AsRef<WorkspaceRuntime> + AsMut<WorkspaceRuntime> + Send + 'static
+{
+	let mut sync_dirs = true;
+
+	loop {
+		if sync_dirs {
+			let workspace = match workspace.upgrade() {
+				Some(w) => { w }
+				None => { return }
+			};
+
+			let dirs = workspace.lock().await
+				.as_ref()
+				.packs()
+				.map(|p| p.dir().ref_inner().clone())
+				.collect::<Vec<_>>();
+			if let Err(e) = watcher.watch(&dirs) {
+				emit(&handle, &name, Vec::new(), vec![e.to_string()]);
+			}
+			sync_dirs = false;
+		}
+
+		let paths = select! {
+			paths = watcher.next() => { paths }
+			_ = packs_changed.notified() => {
+				sync_dirs = true;
+				continue
+			}
+		};
+
+		let workspace = match workspace.upgrade() {
+			Some(w) => { w }
+			None => { return }
+		};
+
+		let results = workspace.lock().await.as_mut().reload_changed(&paths).await;
+		if results.is_empty() { continue }
+
+		let mut reloaded = Vec::with_capacity(results.len());
+		let mut errors = Vec::new();
+		for (changed, result) in results {
+			match result {
+				Ok(()) => { reloaded.push(changed.to_string()) }
+				Err(e) => { errors.push(format!("{changed}: {e}")) }
+			}
+		}
+
+		emit(&handle, &name, reloaded, errors);
+	}
+}
+
+fn emit<R: Runtime>(handle: &AppHandle<R>, name: &str, reloaded: Vec<String>, errors: Vec<String>) {
+	let workspace = name.into();
+	let payload = PacksReloaded { workspace, reloaded, errors };
+	// nothing to do if there's no one to tell
+	let _ = handle.emit(PACKS_RELOADED_EVENT, payload);
+}
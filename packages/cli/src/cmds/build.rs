@@ -0,0 +1,60 @@
+use crate::error::*;
+use ::mc_versions::MCVersion;
+use super::{ Source, StrictnessArgs };
+use ::wiwipaccer_core::build::{ self, DirOutput, ZipOutput };
+use ::wiwipaccer_core::load::Strictness;
+
+/// Builds a resource pack, into a directory or a zip file. Packs are loaded in
+/// strict mode unless `--lenient` is passed, even if the workspace is lenient,
+/// so a pack is never built with parts of it missing by accident
+#[derive(::clap::Args)]
+pub struct Args {
+	#[command(flatten)]
+	source: Source,
+
+	#[command(flatten)]
+	strictness: StrictnessArgs,
+
+	/// Minecraft version to build for. Defaults to the workspace's
+	#[arg(long, short)]
+	mc_version: Option<String>,
+
+	/// Option to use for its texture, as `pack:texture:option`. Can be passed
+	/// more than once, and replaces what the workspace selected for that texture
+	#[arg(long = "select", short, value_name = "OPTION_ID")]
+	selections: Vec<String>,
+
+	/// Where to write the pack. If it ends with `.zip`, a zip file is written
+	/// (replacing any existing file), otherwise it's a directory, which has to
+	/// be empty if it exists already
+	#[arg(long, short)]
+	out: String
+}
+
+pub async fn run(args: Args) -> Result<()> {
+	let Args { source, strictness, mc_version, selections, out } = args;
+
+	let strictness = strictness.get().unwrap_or(Strictness::Strict);
+	let workspace = source.load(Some(strictness)).await?;
+	super::print_diagnostics(&workspace);
+
+	let mc_version = match mc_version {
+		Some(mc_version) => { MCVersion::get(&mc_version)? }
+		None => { workspace.mc_version().ref_inner().ok_or(Error::NoMCVersion)? }
+	};
+
+	let mut all_selections = workspace.selections().ref_inner().clone();
+	for option_id in selections {
+		all_selections.select(option_id.parse()?);
+	}
+
+	let files = build::build(&workspace, &all_selections, mc_version).await?;
+	if out.ends_with(".zip") {
+		build::write_to(&files, ZipOutput::new(&out).await?).await?;
+	} else {
+		build::write_to(&files, DirOutput::new(&out).await?).await?;
+	}
+
+	eprintln!("built {} files for minecraft {} into {out}", files.len(), mc_version.name);
+	Ok(())
+}
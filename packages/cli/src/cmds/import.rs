@@ -0,0 +1,42 @@
+use crate::error::*;
+use ::wiwipaccer_core::import;
+
+/// Imports a resource pack (a zip file or a dir) as a new pack source, with a
+/// texture for each asset dir in it. Never overwrites anything
+#[derive(::clap::Args)]
+pub struct Args {
+	/// Resource pack zip file or dir to import
+	source: String,
+
+	/// Dir to create the pack source in (which doesn't have to be empty)
+	dest: String,
+
+	/// Defaults to the resource pack's file name, with invalid characters
+	/// replaced with underscores
+	#[arg(long, short)]
+	id: Option<String>,
+
+	/// Defaults to the resource pack's file name
+	#[arg(long, short)]
+	name: Option<String>
+}
+
+pub async fn run(args: Args) -> Result<()> {
+	// canonicalised so that `.` and such still have a file name
+	let source = ::tokio::fs::canonicalize(&args.source)
+		.await
+		.map_err(|error| Error::PackDir { path: args.source.clone(), error })?;
+	let stem = source.file_stem()
+		.and_then(|stem| stem.to_str())
+		.ok_or(Error::NonUtf8Path)?;
+	let id = args.id.unwrap_or_else(|| import::sanitise_id(stem));
+	let name = args.name.as_deref().unwrap_or(stem);
+
+	let import = import::import_resource_pack(&args.source, &args.dest, &id, name).await?;
+
+	for skipped in import.skipped() {
+		eprintln!("skipped {} ({})", skipped.path(), skipped.reason());
+	}
+	println!("imported {} texture(s) into pack {id}", import.textures().len());
+	Ok(())
+}
@@ -0,0 +1,165 @@
+use crate::error::*;
+use super::{ Source, StrictnessArgs };
+use ::mc_versions::{ MCVersion, MCVersionRef };
+use ::wiwipaccer_core::locale::Localised;
+use ::wiwipaccer_core::provider2::PackVersionSpecRuntime;
+use ::wiwipaccer_core::workspace2::WorkspaceRuntime;
+
+/// Prints the packs, textures, options and providers that were loaded, and
+/// any problems found while loading them. Packs are loaded with the workspace's
+/// strictness (strict, without a workspace) unless `--strict` or `--lenient`
+/// is passed
+#[derive(::clap::Args)]
+pub struct Args {
+	#[command(flatten)]
+	source: Source,
+
+	#[command(flatten)]
+	strictness: StrictnessArgs,
+
+	/// Only show providers available for this minecraft version
+	#[arg(long, short)]
+	mc_version: Option<String>,
+
+	/// Locale to show names in (ex. `en-GB`). Can be passed more than once,
+	/// most preferred first
+	#[arg(long = "locale", short, value_name = "LOCALE")]
+	locales: Vec<String>
+}
+
+pub async fn run(args: Args) -> Result<()> {
+	let Args { source, strictness, mc_version, locales } = args;
+	let mc_version = mc_version.as_deref().map(MCVersion::get).transpose()?;
+
+	let workspace = source.load(strictness.get()).await?;
+	print!("{}", tree(&workspace, mc_version, &locales));
+	Ok(())
+}
+
+fn tree(workspace: &WorkspaceRuntime, mc_version: Option<MCVersionRef>, locales: &[String]) -> String {
+	let name = |name: &Localised| format!("{:?}", name.get(locales));
+	let mut out = String::new();
+
+	for pack in workspace.packs() {
+		let version = pack.version().ref_inner()
+			.as_ref()
+			.map(|v| format!(" {v}"))
+			.unwrap_or_default();
+		out.push_str(&format!("pack {}{version}: {}\n", pack.id(), name(pack.name().ref_inner())));
+
+		for texture in pack.textures().ref_inner().values() {
+			out.push_str(&format!("  texture {}: {}", texture.id(), name(texture.name().ref_inner())));
+			if let Some(extends) = texture.extends().ref_inner() {
+				out.push_str(&format!(" (extends {}:{})", extends.pack_id(), extends.texture_id()));
+			}
+			out.push('\n');
+
+			for option in texture.options().ref_inner().values() {
+				let is_default = texture.default().ref_inner().as_ref() == Some(option.id());
+				let default = if is_default { " (default)" } else { "" };
+				out.push_str(&format!("    option {}: {}{default}\n", option.id(), name(option.name().ref_inner())));
+
+				let providers = option.providers().ref_inner()
+					.values()
+					.filter(|p| mc_version.map(|v| p.is_available_for(v)).unwrap_or(true));
+				for provider in providers {
+					out.push_str(&format!("      provider {}", provider.id()));
+					if let Some(versions) = provider.versions().ref_inner() {
+						let versions = versions.iter().map(version_spec).collect::<Vec<_>>();
+						out.push_str(&format!(" ({})", versions.join(", ")));
+					}
+					out.push('\n');
+				}
+			}
+		}
+
+		for diagnostic in pack.diagnostics().ref_inner() {
+			for line in diagnostic.to_string().lines() {
+				out.push_str(&format!("  {line}\n"));
+			}
+		}
+	}
+
+	out
+}
+
+fn version_spec(spec: &PackVersionSpecRuntime) -> String {
+	use PackVersionSpecRuntime::*;
+	match spec {
+		PackVersion(pack_format) => { format!("pack format {pack_format}") }
+		MCVersion(version) => { version.name.into() }
+		MCVersionRange(versions) => {
+			// newest first
+			let oldest = versions.last().map(|v| v.name).unwrap_or_default();
+			let newest = versions.first().map(|v| v.name).unwrap_or_default();
+			format!("{oldest} to {newest}")
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cmds::fixture;
+	use ::wiwipaccer_core::load::Strictness;
+
+	async fn load(packs: &[&str]) -> WorkspaceRuntime {
+		let packs = packs.iter()
+			.map(|pack| fixture(pack))
+			.collect();
+		let source = Source { workspace: None, packs };
+		source.load(Some(Strictness::Lenient)).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn tree_shows_what_was_loaded() {
+		let workspace = load(&["basic", "addon"]).await;
+		let expected = "\
+pack basic 1.0.0: \"Basic\"
+  texture dirt: \"Dirt\"
+    option handmade: \"Handmade\" (default)
+      provider all
+  texture stone: \"Stone\"
+    option plain: \"Plain\"
+      provider all (1.13 to 1.20.4)
+    option random: \"Random\" (default)
+      provider all
+pack addon 1.0.0: \"Addon\"
+  texture more-stone: \"More stone\" (extends basic:stone)
+    option extra: \"extra\" (default)
+      provider all
+    option random: \"random\"
+      provider all
+";
+		assert_eq!(expected, tree(&workspace, None, &[]));
+	}
+
+	#[tokio::test]
+	async fn tree_uses_mc_version_and_locales() {
+		let workspace = load(&["basic", "addon"]).await;
+		let mc_version = MCVersion::get("1.12").unwrap();
+		let tree = tree(&workspace, Some(mc_version), &["de".into()]);
+
+		// only available from 1.13
+		assert!(tree.contains("    option plain: \"Plain\"\n    option random"));
+		assert!(tree.contains("pack addon 1.0.0: \"Erweiterung\"\n"));
+		assert!(tree.contains("    option extra: \"zusätzlich\" (default)\n"));
+	}
+
+	#[tokio::test]
+	async fn tree_shows_problems() {
+		let workspace = load(&["broken-many"]).await;
+		let tree = tree(&workspace, None, &[]);
+		assert_eq!(3, tree.lines().filter(|l| l.starts_with("  error in ")).count());
+	}
+
+	#[test]
+	fn version_specs_are_readable() {
+		let mc_version = MCVersion::get("1.20.4").unwrap();
+		let range = MCVersion::get_range("1.13", "1.16.5").unwrap();
+
+		assert_eq!("pack format 15", version_spec(&PackVersionSpecRuntime::PackVersion(15)));
+		assert_eq!("1.20.4", version_spec(&PackVersionSpecRuntime::MCVersion(mc_version)));
+		assert_eq!("1.13 to 1.16.5", version_spec(&PackVersionSpecRuntime::MCVersionRange(range)));
+	}
+}
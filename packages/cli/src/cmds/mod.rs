@@ -0,0 +1,102 @@
+pub mod build;
+pub mod import;
+pub mod inspect;
+pub mod new;
+pub mod schema;
+pub mod validate;
+pub mod versions;
+
+use crate::error::*;
+use ::wiwipaccer_core::load::Strictness;
+use ::wiwipaccer_core::workspace2::{ self, WorkspaceRuntime };
+
+/// name of the workspace packs are loaded into, if no workspace file is passed
+const WORKSPACE_NAME: &str = "wiwipaccer-cli";
+
+/// Path to one of core's fixture packs, so the tests share them with core
+#[cfg(test)]
+fn fixture(pack: &str) -> String {
+	format!("{}/../core/test/fixtures/packs/{pack}", env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Where packs come from, shared by all commands that load packs
+#[derive(::clap::Args)]
+pub struct Source {
+	/// Workspace file to load packs, selections and the minecraft version from
+	/// (a workspace config, in ron)
+	#[arg(long, short)]
+	workspace: Option<String>,
+
+	/// Pack source dirs to load, after (layered on top of) the packs in the
+	/// workspace. Can be in any order, dependencies are loaded first.
+	packs: Vec<String>
+}
+
+impl Source {
+	/// Loads the packs, with the provided strictness if there is one, otherwise
+	/// the workspace's
+	async fn load(&self, strictness: Option<Strictness>) -> Result<WorkspaceRuntime> {
+		let mut workspace = match &self.workspace {
+			Some(path) => {
+				let config = ::tokio::fs::read_to_string(path)
+					.await
+					.map_err(|error| Error::ReadWorkspace { path: path.clone(), error })?;
+				WorkspaceRuntime::from_config_str(&config).await?
+			}
+			None => {
+				WorkspaceRuntime::new(workspace2::nr::Name::new(WORKSPACE_NAME.into()))
+			}
+		};
+
+		if let Some(strictness) = strictness {
+			workspace.set_strictness(strictness);
+		}
+
+		workspace.add_packs(&self.packs).await?;
+
+		Ok(workspace)
+	}
+}
+
+/// Whether parts of packs that fail to load fail the whole load, for commands
+/// that leave that up to the user
+#[derive(::clap::Args)]
+pub struct StrictnessArgs {
+	/// Fail if any part of a pack fails to load
+	#[arg(long, conflicts_with = "lenient")]
+	strict: bool,
+
+	/// Leave parts of packs that fail to load out, and print why, instead of
+	/// failing
+	#[arg(long)]
+	lenient: bool
+}
+
+impl StrictnessArgs {
+	/// The strictness that was passed, if one was
+	fn get(&self) -> Option<Strictness> {
+		match (self.strict, self.lenient) {
+			(true, _) => { Some(Strictness::Strict) }
+			(_, true) => { Some(Strictness::Lenient) }
+			_ => { None }
+		}
+	}
+}
+
+/// Prints problems found while loading packs, since in lenient mode the broken
+/// parts are left out of the packs instead of failing to load, and selections
+/// and the minecraft version in the workspace that were left out because they
+/// couldn't be parsed
+fn print_diagnostics(workspace: &WorkspaceRuntime) {
+	for selection in workspace.invalid_selections().ref_inner() {
+		eprintln!("warning: ignoring invalid selection {selection:?} in the workspace");
+	}
+	if let Some(mc_version) = workspace.invalid_mc_version().ref_inner() {
+		eprintln!("warning: ignoring unknown minecraft version {mc_version:?} in the workspace");
+	}
+	for pack in workspace.packs() {
+		for diagnostic in pack.diagnostics().ref_inner() {
+			eprintln!("{diagnostic}");
+		}
+	}
+}
@@ -0,0 +1,69 @@
+use crate::error::*;
+use ::wiwipaccer_core::scaffold;
+
+/// Creates a new pack, texture, option or provider, with a manifest to start
+/// editing from. Never overwrites anything
+#[derive(::clap::Args)]
+pub struct Args {
+	#[command(subcommand)]
+	entry: Entry
+}
+
+#[derive(::clap::Subcommand)]
+enum Entry {
+	/// Creates a new pack in a dir (which doesn't have to be empty)
+	Pack {
+		dir: String,
+		id: String,
+		/// Defaults to the ID
+		#[arg(long, short)]
+		name: Option<String>
+	},
+	/// Creates a new texture in a pack
+	Texture {
+		pack_dir: String,
+		texture_id: String,
+		/// Defaults to the ID
+		#[arg(long, short)]
+		name: Option<String>
+	},
+	/// Creates a new option for a texture in a pack
+	Option {
+		pack_dir: String,
+		texture_id: String,
+		option_id: String,
+		/// Defaults to the ID
+		#[arg(long, short)]
+		name: Option<String>
+	},
+	/// Creates a new provider for an option in a pack, that copies the files
+	/// put in its `files` dir into the built pack
+	Provider {
+		pack_dir: String,
+		texture_id: String,
+		option_id: String,
+		provider_id: String
+	}
+}
+
+pub async fn run(args: Args) -> Result<()> {
+	match args.entry {
+		Entry::Pack { dir, id, name } => {
+			let name = name.as_deref().unwrap_or(&id);
+			scaffold::create_pack(&dir, &id, name).await?;
+		}
+		Entry::Texture { pack_dir, texture_id, name } => {
+			let name = name.as_deref().unwrap_or(&texture_id);
+			scaffold::create_texture(&pack_dir, &texture_id, name).await?;
+		}
+		Entry::Option { pack_dir, texture_id, option_id, name } => {
+			let name = name.as_deref().unwrap_or(&option_id);
+			scaffold::create_option(&pack_dir, &texture_id, &option_id, name).await?;
+		}
+		Entry::Provider { pack_dir, texture_id, option_id, provider_id } => {
+			scaffold::create_provider(&pack_dir, &texture_id, &option_id, &provider_id).await?;
+		}
+	}
+
+	Ok(())
+}
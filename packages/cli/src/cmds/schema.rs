@@ -0,0 +1,41 @@
+use crate::error::*;
+use ::wiwipaccer_core::schema::Manifest;
+
+/// Prints the JSON schema of a manifest, so editors can validate and
+/// autocomplete them, or writes the schemas of all of them to a directory
+#[derive(::clap::Args)]
+pub struct Args {
+	/// Manifest to print the schema of (ex. `pack.wiwimeta`)
+	#[arg(value_parser = parse_manifest, required_unless_present = "out")]
+	manifest: Option<Manifest>,
+
+	/// Directory to write the schemas of all manifests into instead, as
+	/// `<manifest>.schema.json` (ex. `pack.wiwimeta.schema.json`)
+	#[arg(long, short, conflicts_with = "manifest")]
+	out: Option<String>
+}
+
+pub async fn run(args: Args) -> Result<()> {
+	if let Some(manifest) = args.manifest {
+		println!("{}", manifest.schema_json());
+		return Ok(())
+	}
+
+	if let Some(out) = args.out {
+		for manifest in Manifest::ALL {
+			let path = format!("{out}/{}.schema.json", manifest.file_name());
+			::tokio::fs::write(&path, manifest.schema_json())
+				.await
+				.map_err(|error| Error::Write { path, error })?;
+		}
+	}
+
+	Ok(())
+}
+
+fn parse_manifest(file_name: &str) -> Result<Manifest, String> {
+	Manifest::from_file_name(file_name).ok_or_else(|| {
+		let file_names = Manifest::ALL.map(Manifest::file_name);
+		format!("expected one of {}", file_names.join(", "))
+	})
+}
@@ -0,0 +1,72 @@
+use crate::error::*;
+use super::{ Source, StrictnessArgs };
+use ::wiwipaccer_core::error::load_err::Severity;
+use ::wiwipaccer_core::load::Strictness;
+use ::wiwipaccer_core::validate;
+
+/// Checks packs for problems, failing if any errors are found (ex. for a
+/// pre-commit hook). Packs are loaded in strict mode unless `--lenient` is
+/// passed, like `build` does, so a pack that can't be built doesn't pass. With
+/// `--lenient`, every problem in a pack is listed instead of failing on load.
+/// Packs are then checked for dirs that can't be loaded, files that aren't
+/// used, and asset paths minecraft can't load. Warnings are printed too, but
+/// only fail with `--deny-warnings`.
+#[derive(::clap::Args)]
+pub struct Args {
+	#[command(flatten)]
+	source: Source,
+
+	#[command(flatten)]
+	strictness: StrictnessArgs,
+
+	/// Fail if there are any warnings too, not just errors
+	#[arg(long)]
+	deny_warnings: bool
+}
+
+pub async fn run(args: Args) -> Result<()> {
+	let Args { source, strictness, deny_warnings } = args;
+
+	let strictness = strictness.get().unwrap_or(Strictness::Strict);
+	let workspace = source.load(Some(strictness)).await?;
+
+	let mut problems = 0;
+	for pack in workspace.packs() {
+		for diagnostic in validate::validate_pack(pack).await? {
+			println!("{diagnostic}");
+			if diagnostic.severity() == Severity::Error || deny_warnings {
+				problems += 1;
+			}
+		}
+	}
+
+	match problems {
+		0 => { Ok(()) }
+		problems => { Err(Error::Invalid { problems }) }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cmds::fixture;
+
+	fn args(pack: &str, lenient: bool) -> Args {
+		let packs = vec![fixture(pack)];
+		let source = Source { workspace: None, packs };
+		let strictness = StrictnessArgs { strict: false, lenient };
+		Args { source, strictness, deny_warnings: false }
+	}
+
+	#[tokio::test]
+	async fn strict_unless_lenient() {
+		run(args("basic", false)).await.unwrap();
+
+		// fails to load, like it would for build
+		let error = run(args("broken-many", false)).await.unwrap_err();
+		assert!(!matches!(error, Error::Invalid { .. }));
+
+		let error = run(args("broken-many", true)).await.unwrap_err();
+		assert!(matches!(error, Error::Invalid { problems: 3 }));
+	}
+}
@@ -0,0 +1,31 @@
+use crate::error::*;
+use ::mc_versions::{ MC_VERSIONS, PackFormat, ReleaseType };
+
+/// Lists the minecraft versions packs can be built for, newest first, with
+/// their pack format
+#[derive(::clap::Args)]
+pub struct Args {
+	/// Include snapshots and other non release versions
+	#[arg(long, short)]
+	all: bool
+}
+
+pub async fn run(args: Args) -> Result<()> {
+	let versions = MC_VERSIONS.iter()
+		.filter(|v| args.all || matches!(v.release_type, ReleaseType::Release));
+
+	for version in versions {
+		match version.pack_format {
+			PackFormat::Verified(pack_format) => {
+				println!("{}\t{pack_format}", version.name);
+			}
+			PackFormat::Unverified(pack_format) => {
+				println!("{}\t{pack_format} (unverified)", version.name);
+			}
+			// can't build for these
+			PackFormat::None | PackFormat::Unknown => {}
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,111 @@
+use ::std::result::Result as StdResult;
+use ::thiserror::Error;
+use ::ts_result::NiceErrorMessage;
+use ::wiwipaccer_core::error::{ build_err, import_err, scaffold_err, validate_err };
+
+pub type Result<T, E = Error> = StdResult<T, E>;
+
+// named like the app's errors
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("{}", .0.to_error_message())]
+	BuildError(build_err::Build),
+
+	#[error("{}", .0.to_error_message())]
+	ImportError(import_err::ImportPack),
+
+	#[error("found {problems} problem(s) in the pack(s)")]
+	Invalid {
+		problems: usize
+	},
+
+	#[error("{}", .0.to_error_message())]
+	MCVersionError(::mc_versions::error::InGetting),
+
+	#[error("no minecraft version to build for (pass one with --mc-version, or set one in the workspace)")]
+	NoMCVersion,
+
+	#[error("non UTF-8 paths are not supported")]
+	NonUtf8Path,
+
+	#[error("couldn't find pack source dir {path}:\n{error}")]
+	PackDir {
+		path: String,
+		error: ::std::io::Error
+	},
+
+	#[error("invalid option ID passed to --select:\n{0}")]
+	OptionIDError(#[from] ::wiwipaccer_common::ParseOptionIDError),
+
+	#[error("couldn't read workspace file {path}:\n{error}")]
+	ReadWorkspace {
+		path: String,
+		error: ::std::io::Error
+	},
+
+	#[error("{}", .0.to_error_message())]
+	ScaffoldError(scaffold_err::Scaffold),
+
+	#[error("{}", .0.to_error_message())]
+	ValidateError(validate_err::ValidatePack),
+
+	#[error(transparent)]
+	WorkspaceError(#[from] ::wiwipaccer_core::workspace2::Error),
+
+	#[error("couldn't write {path}:\n{error}")]
+	Write {
+		path: String,
+		error: ::std::io::Error
+	},
+
+	#[error("{}", .0.to_error_message())]
+	WriteDirError(build_err::WriteDir),
+
+	#[error("{}", .0.to_error_message())]
+	WriteZipError(build_err::WriteZip)
+}
+
+// these only implement `NiceErrorMessage`, so can't use `#[from]`
+
+impl From<build_err::Build> for Error {
+	fn from(error: build_err::Build) -> Self {
+		Self::BuildError(error)
+	}
+}
+
+impl From<import_err::ImportPack> for Error {
+	fn from(error: import_err::ImportPack) -> Self {
+		Self::ImportError(error)
+	}
+}
+
+impl From<::mc_versions::error::InGetting> for Error {
+	fn from(error: ::mc_versions::error::InGetting) -> Self {
+		Self::MCVersionError(error)
+	}
+}
+
+impl From<scaffold_err::Scaffold> for Error {
+	fn from(error: scaffold_err::Scaffold) -> Self {
+		Self::ScaffoldError(error)
+	}
+}
+
+impl From<validate_err::ValidatePack> for Error {
+	fn from(error: validate_err::ValidatePack) -> Self {
+		Self::ValidateError(error)
+	}
+}
+
+impl From<build_err::WriteDir> for Error {
+	fn from(error: build_err::WriteDir) -> Self {
+		Self::WriteDirError(error)
+	}
+}
+
+impl From<build_err::WriteZip> for Error {
+	fn from(error: build_err::WriteZip) -> Self {
+		Self::WriteZipError(error)
+	}
+}
@@ -0,0 +1,50 @@
+//! Command line interface for building and inspecting packs without the app
+//! (ex. in CI). Doesn't depend on tauri or surrealdb.
+
+// TODO: remove this when core's old code is cleaned up
+#![allow(deprecated)]
+
+mod cmds;
+mod error;
+
+use ::clap::{ Parser, Subcommand };
+use ::std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+	#[command(subcommand)]
+	command: Command
+}
+
+#[derive(Subcommand)]
+enum Command {
+	Build(cmds::build::Args),
+	Import(cmds::import::Args),
+	Inspect(cmds::inspect::Args),
+	New(cmds::new::Args),
+	Validate(cmds::validate::Args),
+	Versions(cmds::versions::Args),
+	Schema(cmds::schema::Args)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	let result = match Cli::parse().command {
+		Command::Build(args) => { cmds::build::run(args).await }
+		Command::Import(args) => { cmds::import::run(args).await }
+		Command::Inspect(args) => { cmds::inspect::run(args).await }
+		Command::New(args) => { cmds::new::run(args).await }
+		Command::Validate(args) => { cmds::validate::run(args).await }
+		Command::Versions(args) => { cmds::versions::run(args).await }
+		Command::Schema(args) => { cmds::schema::run(args).await }
+	};
+
+	match result {
+		Ok(()) => { ExitCode::SUCCESS }
+		Err(error) => {
+			eprintln!("{error}");
+			ExitCode::FAILURE
+		}
+	}
+}
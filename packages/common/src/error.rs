@@ -76,6 +76,33 @@ impl NiceErrorMessage for OptionIDError {
 impl_display!(OptionIDError);
 impl Error for OptionIDError {}
 
+#[derive(Debug)]
+pub enum ParseOptionIDError {
+	WrongComponentCount {
+		id: String,
+		count: usize
+	},
+	Invalid(OptionIDError)
+}
+
+impl NiceErrorMessage for ParseOptionIDError {
+	fn fmt(&self, f: &mut Formatter) {
+		use ParseOptionIDError::*;
+		match self {
+			WrongComponentCount { id, count } => {
+				f.write_line_args(format_args!("provided option ID: {id}"));
+				f.write_args(format_args!("expected 3 components separated by ':' (pack:texture:option), found {count}"));
+			}
+			Invalid(error) => {
+				f.fmt(error);
+			}
+		}
+	}
+}
+
+impl_display!(ParseOptionIDError);
+impl Error for ParseOptionIDError {}
+
 #[derive(Debug)]
 pub struct MinecraftIDError {
 	pub(crate) ns: Option<Box<ComponentError>>,
@@ -137,6 +164,15 @@ mod tests {
 		assert_eq!(expected, &*error);
 	}
 
+	#[test]
+	fn parse_option_id_error_message() {
+		let expected = include_str!("../test/fixtures/parse-option-id-error-message.txt");
+		let error = "lt:stone".parse::<OptionID>()
+			.unwrap_err()
+			.to_error_message();
+		assert_eq!(expected, &*error);
+	}
+
 	#[test]
 	fn mc_id_error_message() {
 		let expected = include_str!("../test/fixtures/minecraft-id-error-message.txt");
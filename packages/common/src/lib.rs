@@ -4,6 +4,6 @@ mod error;
 pub mod mc_id;
 pub mod option_id;
 
-pub use self::error::OptionIDError;
+pub use self::error::{ MinecraftIDError, OptionIDError, ParseOptionIDError };
 pub use self::mc_id::MinecraftID;
 pub use self::option_id::OptionID;
@@ -1,6 +1,10 @@
 use crate::error::{ ComponentError, MinecraftIDError };
 use ::boxed::Boxed as _;
 use ::std::fmt::{ self, Display };
+use ::std::str::FromStr;
+
+/// Namespace used when parsing an ID that doesn't specify one
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
 
 /// Minecraft block/item ID
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -33,6 +37,22 @@ impl Display for MinecraftID {
 	}
 }
 
+/// Parses `namespace:id`, or just `id`, in which case the namespace will be
+/// [`DEFAULT_NAMESPACE`], same as the game does
+impl FromStr for MinecraftID {
+	type Err = MinecraftIDError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (ns, id) = s.split_once(':')
+			.unwrap_or((DEFAULT_NAMESPACE, s));
+
+		MinecraftID::builder()
+			.namespace(ns)
+			.id(id)
+			.build()
+	}
+}
+
 pub struct IDBuilder {
 	__private: ()
 }
@@ -97,7 +117,7 @@ impl<'h> WithID<'h> {
 
 /// Minecraft ID components are only allowed to contain loweralpha, numeric, and
 /// underscore characters (I think? ~vt)
-pub(crate) fn invalid_chars(id_component: &str) -> Option<Vec<char>> {
+pub fn invalid_chars(id_component: &str) -> Option<Vec<char>> {
 	#[inline]
 	fn char_is_valid(c: char) -> bool {
 		matches!(c, 'a'..='z' | '0'..='9' | '_')
@@ -182,4 +202,22 @@ mod tests {
 			.to_string();
 		assert_eq!("the_vault:vault_diamond", &*vault_diamond);
 	}
+
+	#[test]
+	fn from_str() {
+		let vault_diamond = "the_vault:vault_diamond".parse::<MinecraftID>().unwrap();
+		assert_eq!("the_vault", vault_diamond.ns_ref());
+		assert_eq!("vault_diamond", vault_diamond.id_ref());
+
+		// namespace defaults to minecraft
+		let stone = "stone".parse::<MinecraftID>().unwrap();
+		assert_eq!("minecraft", stone.ns_ref());
+		assert_eq!("stone", stone.id_ref());
+
+		// more than one colon ends up in the ID, which makes it invalid
+		"minecraft:stone:extra".parse::<MinecraftID>().unwrap_err();
+
+		// invalid namespace
+		"mine craft:stone".parse::<MinecraftID>().unwrap_err();
+	}
 }
@@ -1,6 +1,7 @@
-use crate::error::{ ComponentError, OptionIDError };
+use crate::error::{ ComponentError, OptionIDError, ParseOptionIDError };
 use ::boxed::Boxed as _;
 use ::std::fmt::{ self, Display };
+use ::std::str::FromStr;
 
 /// An ID for an option, consisting of the pack its from, the texture its for,
 /// and its own option-specific ID
@@ -40,6 +41,28 @@ impl Display for OptionID {
 	}
 }
 
+/// Parses the format written by the [`Display`] impl (`pack:texture:option`)
+impl FromStr for OptionID {
+	type Err = ParseOptionIDError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let components = s.split(':').collect::<Vec<_>>();
+
+		let [pack_id, texture_id, option_id] = &*components else {
+			let id = s.into();
+			let count = components.len();
+			return Err(ParseOptionIDError::WrongComponentCount { id, count })
+		};
+
+		OptionID::builder()
+			.pack_id(pack_id)
+			.texture_id(texture_id)
+			.option_id(option_id)
+			.build()
+			.map_err(ParseOptionIDError::Invalid)
+	}
+}
+
 pub struct IDBuilder {
 	__private: ()
 }
@@ -219,4 +242,24 @@ mod tests {
 			.to_string();
 		assert_eq!("lt:stone:random", &*lt_stone);
 	}
+
+	#[test]
+	fn from_str() {
+		let lt_stone = "lt:stone:random".parse::<OptionID>().unwrap();
+		assert_eq!("lt", lt_stone.pack_id_ref());
+		assert_eq!("stone", lt_stone.texture_id_ref());
+		assert_eq!("random", lt_stone.option_id_ref());
+
+		// roundtrips with display
+		assert_eq!(lt_stone, lt_stone.to_string().parse::<OptionID>().unwrap());
+
+		// too few components
+		"lt:stone".parse::<OptionID>().unwrap_err();
+
+		// too many components
+		"lt:stone:random:extra".parse::<OptionID>().unwrap_err();
+
+		// invalid component
+		"lt:stone:ra ndom".parse::<OptionID>().unwrap_err();
+	}
 }
@@ -0,0 +1,458 @@
+//! Builds a resource pack out of a loaded workspace
+
+mod output;
+
+pub use self::output::{ Output, DirOutput, ZipOutput };
+use crate::error::build_err;
+use crate::option2;
+use crate::pack2::PackRuntime;
+use crate::texture2::{ self, MergedTexture };
+use crate::workspace2::WorkspaceRuntime;
+use ::mc_versions::MCVersionRef;
+use ::serde::Serialize;
+use ::std::collections::BTreeMap;
+use ::std::collections::btree_map::Entry;
+use ::wiwipaccer_common::OptionID;
+
+const PACK_MCMETA: &str = "pack.mcmeta";
+
+/// Which option is picked for which texture. Textures that don't have a
+/// selection will use their default option, or be left out of the built
+/// pack if they don't have one.
+///
+/// Iteration is sorted by pack ID then texture ID.
+#[derive(Clone, Debug, Default)]
+pub struct Selections {
+	packs: BTreeMap<String, BTreeMap<String, OptionID>>
+}
+
+impl Selections {
+	#[inline]
+	pub fn new() -> Self {
+		let packs = BTreeMap::new();
+		Self { packs }
+	}
+
+	/// Selects an option for the texture it belongs to, returning the option
+	/// that was selected for that texture before, if there was one
+	pub fn select(&mut self, option_id: OptionID) -> Option<OptionID> {
+		self.packs.entry(option_id.pack_id_ref().into())
+			.or_default()
+			.insert(option_id.texture_id_ref().into(), option_id)
+	}
+
+	/// Clears the selection for a texture, so it goes back to its default
+	/// option, returning the option that was selected, if there was one
+	pub fn deselect(&mut self, pack_id: &str, texture_id: &str) -> Option<OptionID> {
+		let textures = self.packs.get_mut(pack_id)?;
+		let previous = textures.remove(texture_id);
+		if textures.is_empty() {
+			self.packs.remove(pack_id);
+		}
+		previous
+	}
+
+	/// Clears the selections for all textures in a pack
+	#[inline]
+	pub fn deselect_pack(&mut self, pack_id: &str) {
+		self.packs.remove(pack_id);
+	}
+
+	#[inline]
+	pub fn get(&self, pack_id: &str, texture_id: &str) -> Option<&OptionID> {
+		self.packs.get(pack_id)?.get(texture_id)
+	}
+
+	#[inline]
+	pub fn iter(&self) -> impl Iterator<Item = &OptionID> {
+		self.packs.values().flat_map(|p| p.values())
+	}
+}
+
+/// Files that make up a built pack, keyed by their path relative to the
+/// root of the pack
+///
+/// Iteration is sorted by path, so writing the same files always happens in
+/// the same order, regardless of the order they were added in.
+#[derive(Debug, Default)]
+pub struct Files {
+	files: BTreeMap<String, Vec<u8>>
+}
+
+impl Files {
+	#[inline]
+	pub fn new() -> Self {
+		let files = BTreeMap::new();
+		Self { files }
+	}
+
+	pub fn add_file(&mut self, path: String, contents: Vec<u8>) -> Result<(), build_err::AddFile> {
+		match self.files.entry(path) {
+			Entry::Occupied(entry) => {
+				Err(build_err::duplicate_file(entry.key().clone()))
+			}
+			Entry::Vacant(entry) => {
+				entry.insert(contents);
+				Ok(())
+			}
+		}
+	}
+
+	pub fn add_json<T>(&mut self, path: String, value: &T) -> Result<(), build_err::AddFile>
+	where
+		T: ?Sized + Serialize
+	{
+		match ::serde_json::to_vec_pretty(value) {
+			Ok(contents) => { self.add_file(path, contents) }
+			Err(error) => { Err(build_err::json(path, error)) }
+		}
+	}
+
+	#[inline]
+	pub fn get(&self, path: &str) -> Option<&[u8]> {
+		self.files.get(path).map(|f| &**f)
+	}
+
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.files.len()
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.files.is_empty()
+	}
+
+	#[inline]
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+		self.files.iter().map(|(p, c)| (&**p, &**c))
+	}
+
+	/// Puts all files in `top` on top of the files in `self`, replacing any
+	/// files that exist in both
+	#[inline]
+	fn layer(&mut self, top: Files) {
+		self.files.extend(top.files);
+	}
+}
+
+#[derive(Serialize)]
+struct PackMcmeta<'h> {
+	pack: PackMcmetaPack<'h>
+}
+
+#[derive(Serialize)]
+struct PackMcmetaPack<'h> {
+	pack_format: u8,
+	description: &'h str
+}
+
+/// Builds the pack in memory. Packs are layered in the order they are in the
+/// workspace, so files from packs later in the list replace files from packs
+/// earlier in the list.
+pub async fn build(
+	workspace: &WorkspaceRuntime,
+	selections: &Selections,
+	mc_version: MCVersionRef
+) -> Result<Files, build_err::Build> {
+	let pack_format = mc_version.pack_format
+		.get_version()
+		.ok_or_else(|| build_err::no_pack_format(mc_version.name))?;
+
+	check_selections(workspace, selections)?;
+
+	let mut files = Files::new();
+
+	for pack in workspace.packs() {
+		let mut pack_files = Files::new();
+
+		// extending textures are built as part of the texture they extend
+		let textures = pack.textures().ref_inner()
+			.values()
+			.filter(|t| t.extends().ref_inner().is_none());
+
+		for texture in textures {
+			let texture = workspace.merged_texture(pack, texture);
+			build_texture(pack, &texture, selections, mc_version, &mut pack_files).await?;
+		}
+
+		files.layer(pack_files);
+	}
+
+	let description = workspace.name().ref_inner();
+	let pack = PackMcmetaPack { pack_format, description };
+	files.add_json(PACK_MCMETA.into(), &PackMcmeta { pack })?;
+
+	Ok(files)
+}
+
+/// Builds the pack, and writes it to the provided output
+pub async fn build_to<O: Output>(
+	workspace: &WorkspaceRuntime,
+	selections: &Selections,
+	mc_version: MCVersionRef,
+	output: O
+) -> Result<(), build_err::BuildTo<O::Error>> {
+	let files = build(workspace, selections, mc_version).await?;
+	write_to(&files, output).await.map_err(build_err::output)
+}
+
+/// Writes already built files to the provided output. If writing a file
+/// fails, the output is aborted
+pub async fn write_to<O: Output>(files: &Files, mut output: O) -> Result<(), O::Error> {
+	for (path, contents) in files.iter() {
+		if let Err(e) = output.write_file(path, contents).await {
+			output.abort().await;
+			return Err(e)
+		}
+	}
+
+	output.finish().await
+}
+
+fn check_selections(
+	workspace: &WorkspaceRuntime,
+	selections: &Selections
+) -> Result<(), build_err::Build> {
+	for option_id in selections.iter() {
+		let pack_id = option_id.pack_id_ref();
+		let texture_id = option_id.texture_id_ref();
+
+		let pack = workspace.packs()
+			.find(|p| p.id().ref_inner() == pack_id)
+			.ok_or_else(|| build_err::unknown_pack(pack_id))?;
+
+		let texture = pack.textures().ref_inner()
+			.get(&texture2::nr::ID::new(texture_id.into()))
+			.ok_or_else(|| build_err::unknown_texture(pack_id, texture_id))?;
+
+		if let Some(extends) = texture.extends().ref_inner() {
+			return Err(build_err::selected_extending_texture(
+				pack_id,
+				texture_id,
+				extends.pack_id().ref_inner(),
+				extends.texture_id().ref_inner()
+			))
+		}
+	}
+
+	Ok(())
+}
+
+async fn build_texture(
+	pack: &PackRuntime,
+	texture: &MergedTexture<'_>,
+	selections: &Selections,
+	mc_version: MCVersionRef,
+	files: &mut Files
+) -> Result<(), build_err::Build> {
+	let pack_id = pack.id().ref_inner();
+	let texture_id = texture.texture().id().ref_inner();
+
+	let option_id = match selections.get(pack_id, texture_id) {
+		Some(selected) => { selected.option_id_ref() }
+		None => match texture.default() {
+			Some(default) => { default.ref_inner() }
+			None => { return Ok(()) }
+		}
+	};
+
+	let option = texture.options()
+		.get(&option2::nr::ID::new(option_id.into()))
+		.ok_or_else(|| build_err::unknown_option(pack_id, texture_id, option_id))?;
+
+	let provider = option.provider_for(mc_version)
+		.ok_or_else(|| build_err::no_provider_available(
+			pack_id,
+			texture_id,
+			option_id,
+			mc_version.name
+		))?;
+
+	provider.generate(mc_version, files).await?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_util::{ self, fixture, mc_version };
+
+	fn option_id(pack_id: &str, texture_id: &str, option_id: &str) -> OptionID {
+		OptionID::builder()
+			.pack_id(pack_id)
+			.texture_id(texture_id)
+			.option_id(option_id)
+			.build()
+			.unwrap()
+	}
+
+	#[test]
+	fn selections_replace_per_texture() {
+		let mut selections = Selections::new();
+
+		assert!(selections.select(option_id("lt", "stone", "random")).is_none());
+		assert!(selections.select(option_id("lt", "dirt", "random")).is_none());
+
+		let previous = selections.select(option_id("lt", "stone", "plain")).unwrap();
+		assert_eq!("random", previous.option_id_ref());
+
+		assert_eq!("plain", selections.get("lt", "stone").unwrap().option_id_ref());
+		assert_eq!("random", selections.get("lt", "dirt").unwrap().option_id_ref());
+		assert!(selections.get("lt", "grass").is_none());
+		assert_eq!(2, selections.iter().count());
+
+		assert_eq!("plain", selections.deselect("lt", "stone").unwrap().option_id_ref());
+		assert!(selections.deselect("lt", "stone").is_none());
+		assert!(selections.deselect("nope", "stone").is_none());
+		assert_eq!(1, selections.iter().count());
+	}
+
+	async fn basic_workspace() -> WorkspaceRuntime {
+		let dir = fixture("basic");
+		let mut workspace = WorkspaceRuntime::new(crate::workspace2::nr::Name::new("uwu".into()));
+		workspace.add_pack(&dir).await.unwrap();
+		workspace
+	}
+
+	#[tokio::test]
+	async fn build_uses_default_option() {
+		let workspace = basic_workspace().await;
+		let files = build(&workspace, &Selections::new(), mc_version("1.20.4")).await.unwrap();
+
+		let pack_mcmeta = ::serde_json::from_slice::<::serde_json::Value>(files.get("pack.mcmeta").unwrap()).unwrap();
+		assert_eq!(pack_mcmeta, ::serde_json::json!({
+			"pack": { "pack_format": 22, "description": "uwu" }
+		}));
+
+		assert!(files.get("assets/minecraft/blockstates/stone.json").is_some());
+		assert!(files.get("assets/minecraft/models/block/stone_mirrored.json").is_some());
+
+		assert_eq!(b"not really a png", files.get("assets/minecraft/textures/block/dirt.png").unwrap());
+		assert_eq!(b"not really a png either", files.get("assets/minecraft/textures/block/extra/dirt_side.png").unwrap());
+	}
+
+	#[tokio::test]
+	async fn build_uses_selection() {
+		let workspace = basic_workspace().await;
+		let mut selections = Selections::new();
+		selections.select(option_id("basic", "stone", "plain"));
+
+		let files = build(&workspace, &selections, mc_version("1.20.4")).await.unwrap();
+		assert!(files.get("assets/minecraft/blockstates/stone.json").is_some());
+		assert!(files.get("assets/minecraft/models/block/stone_mirrored.json").is_none());
+
+		let mut selections = Selections::new();
+		selections.select(option_id("basic", "stone", "nonexistent"));
+		build(&workspace, &selections, mc_version("1.20.4")).await.unwrap_err();
+
+		let mut selections = Selections::new();
+		selections.select(option_id("nonexistent", "stone", "plain"));
+		build(&workspace, &selections, mc_version("1.20.4")).await.unwrap_err();
+	}
+
+	#[tokio::test]
+	async fn build_respects_provider_versions() {
+		let workspace = basic_workspace().await;
+		let mut selections = Selections::new();
+		selections.select(option_id("basic", "stone", "plain"));
+
+		build(&workspace, &selections, mc_version("1.13")).await.unwrap();
+		let error = build(&workspace, &selections, mc_version("1.12.2")).await.unwrap_err();
+		assert!(matches!(error, build_err::Build::NoProviderAvailable { .. }));
+
+		build(&workspace, &Selections::new(), mc_version("1.12.2")).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn build_merges_extending_textures() {
+		let mut workspace = basic_workspace().await;
+		let dir = fixture("addon");
+		workspace.add_pack(&dir).await.unwrap();
+
+		let blockstate = |files: &Files| {
+			let blockstate = files.get("assets/minecraft/blockstates/stone.json").unwrap();
+			::serde_json::from_slice::<::serde_json::Value>(blockstate).unwrap()
+		};
+
+		// addon's default replaces basic's
+		let files = build(&workspace, &Selections::new(), mc_version("1.20.4")).await.unwrap();
+		assert_eq!(270, blockstate(&files)["variants"][""][1]["y"]);
+
+		// addon's option with the same ID replaces basic's
+		let mut selections = Selections::new();
+		selections.select(option_id("basic", "stone", "random"));
+		let files = build(&workspace, &selections, mc_version("1.20.4")).await.unwrap();
+		assert_eq!(90, blockstate(&files)["variants"][""][1]["y"]);
+		assert!(files.get("assets/minecraft/models/block/stone_mirrored.json").is_none());
+
+		// basic's other options are still there
+		let mut selections = Selections::new();
+		selections.select(option_id("basic", "stone", "plain"));
+		build(&workspace, &selections, mc_version("1.20.4")).await.unwrap();
+
+		let mut selections = Selections::new();
+		selections.select(option_id("addon", "more-stone", "extra"));
+		let error = build(&workspace, &selections, mc_version("1.20.4")).await.unwrap_err();
+		assert!(matches!(error, build_err::Build::SelectedExtendingTexture { .. }));
+
+		let frontend = crate::workspace2::FrontendData::new(&workspace, mc_version("1.20.4"), &[]);
+		let frontend = ::serde_json::to_value(frontend).unwrap();
+		let stone = &frontend["packs"][0]["textures"]["stone"];
+		assert_eq!("extra", stone["default"]);
+		let options = stone["options"].as_object().unwrap().keys().collect::<Vec<_>>();
+		assert_eq!(vec!["extra", "plain", "random"], options);
+		assert_eq!("basic:stone", frontend["packs"][1]["textures"]["more-stone"]["extends"]);
+	}
+
+	#[tokio::test]
+	async fn building_twice_gives_the_same_zip() {
+		let scratch = test_util::scratch_dir("build-reproducible");
+
+		let mut zips = Vec::new();
+		for i in 0..2 {
+			// loaded again each time, so nothing is shared between the builds
+			let mut workspace = basic_workspace().await;
+			workspace.add_pack(&fixture("addon")).await.unwrap();
+
+			let mut selections = Selections::new();
+			selections.select(option_id("basic", "stone", "random"));
+
+			let path = scratch.join(format!("pack-{i}.zip"));
+			let path = path.to_str().unwrap();
+			let output = ZipOutput::new(path).await.unwrap();
+			build_to(&workspace, &selections, mc_version("1.20.4"), output).await.unwrap();
+			zips.push(::std::fs::read(path).unwrap());
+		}
+
+		assert_eq!(zips[0], zips[1]);
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn extending_missing_texture_fails_to_load() {
+		let mut workspace = basic_workspace().await;
+		let dir = fixture("addon-bad");
+		workspace.add_pack(&dir).await.unwrap_err();
+
+		// and without its dependency, it fails on that instead
+		let mut workspace = WorkspaceRuntime::new(crate::workspace2::nr::Name::new("uwu".into()));
+		workspace.add_pack(&dir).await.unwrap_err();
+	}
+
+	#[test]
+	fn files_duplicates_and_layering() {
+		let mut bottom = Files::new();
+		bottom.add_file("a".into(), b"bottom a".to_vec()).unwrap();
+		bottom.add_file("b".into(), b"bottom b".to_vec()).unwrap();
+		bottom.add_file("a".into(), b"again".to_vec()).unwrap_err();
+
+		let mut top = Files::new();
+		top.add_file("a".into(), b"top a".to_vec()).unwrap();
+
+		bottom.layer(top);
+		assert_eq!(2, bottom.len());
+		assert_eq!(b"top a", bottom.get("a").unwrap());
+		assert_eq!(b"bottom b", bottom.get("b").unwrap());
+	}
+}
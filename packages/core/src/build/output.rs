@@ -0,0 +1,336 @@
+use crate::error::build_err;
+use crate::util::fs2 as fs;
+use crate::util::safe_path;
+use ::async_trait::async_trait;
+use ::camino::Utf8PathBuf;
+use ::std::fs::File;
+use ::std::io::{ self, BufWriter, Write as _ };
+use ::ts_result::NiceErrorMessage;
+use ::zip::{ CompressionMethod, DateTime, ZipWriter };
+use ::zip::result::ZipResult;
+use ::zip::write::FileOptions;
+
+/// Added to the path of a zip for the file it's written to before it's moved
+/// there, so a build that fails partway doesn't leave a broken zip behind
+const ZIP_TEMP_SUFFIX: &str = ".tmp";
+
+/// Somewhere a built pack can be written to
+#[async_trait]
+pub trait Output: Send {
+	type Error: NiceErrorMessage;
+
+	/// Writes one file, with a path relative to the root of the pack
+	async fn write_file(&mut self, path: &str, contents: &[u8]) -> Result<(), Self::Error>;
+
+	/// Called after all files are written
+	async fn finish(self) -> Result<(), Self::Error>;
+
+	/// Called instead of [`finish`](Output::finish) if writing a file failed,
+	/// to clean up anything that was partially written
+	#[inline]
+	async fn abort(self) where Self: Sized {}
+}
+
+/// Writes files into a plain directory
+pub struct DirOutput {
+	dir: String
+}
+
+impl DirOutput {
+	/// The directory will be created if it doesn't exist, but if it does
+	/// it has to be empty
+	pub async fn new(dir: &str) -> Result<Self, build_err::WriteDir> {
+		fs::create_dir_all(|| dir.into()).await?;
+
+		let mut read_dir = fs::read_dir(|| dir.into()).await?;
+		if read_dir.next().await?.is_some() {
+			return Err(build_err::not_empty(dir.into()))
+		}
+
+		let dir = dir.into();
+		Ok(Self { dir })
+	}
+}
+
+#[async_trait]
+impl Output for DirOutput {
+	type Error = build_err::WriteDir;
+
+	async fn write_file(&mut self, path: &str, contents: &[u8]) -> Result<(), Self::Error> {
+		if !safe_path::is_normalised_relative(path) {
+			return Err(build_err::invalid_path(path.into()))
+		}
+
+		let mut file_path = Utf8PathBuf::from(&*self.dir);
+		file_path.push(path);
+
+		if let Some(parent) = file_path.parent() {
+			fs::create_dir_all(|| parent.as_str().into()).await?;
+		}
+
+		fs::write(|| file_path.as_str().into(), contents.into()).await?;
+		Ok(())
+	}
+
+	#[inline]
+	async fn finish(self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+}
+
+/// Writes files into a zip archive that can be loaded directly by Minecraft.
+/// Files are compressed and written to disk as they come in, so the archive
+/// is never held in memory all at once.
+///
+/// Every entry gets the same timestamp and permissions, so writing the same
+/// files in the same order produces a byte-for-byte identical archive.
+///
+/// The archive is written next to `path`, and only moved there once it's
+/// finished. If it's aborted (or dropped) before then, the unfinished archive
+/// is removed.
+pub struct ZipOutput {
+	path: String,
+	/// `None` once the archive has been moved to `path`
+	temp_path: Option<String>,
+	/// only `None` while it's being written to (on a blocking thread), or if
+	/// that thread panicked
+	writer: Option<ZipWriter<BufWriter<File>>>
+}
+
+impl ZipOutput {
+	/// An existing file at `path` will be replaced, once the archive is finished
+	pub async fn new(path: &str) -> Result<Self, build_err::WriteZip> {
+		let temp_path = format!("{path}{ZIP_TEMP_SUFFIX}");
+		let file = fs::create(|| temp_path.clone()).await?;
+
+		let path = path.into();
+		let temp_path = Some(temp_path);
+		let writer = Some(ZipWriter::new(BufWriter::new(file)));
+		Ok(Self { path, temp_path, writer })
+	}
+
+	/// Runs `f` on a blocking thread, since the zip writer compresses and
+	/// writes to the file as it goes
+	async fn with_writer<F>(&mut self, f: F) -> Result<(), build_err::WriteZip>
+	where
+		F: FnOnce(&mut ZipWriter<BufWriter<File>>) -> ZipResult<()> + Send + 'static
+	{
+		let mut writer = self.writer.take()
+			.ok_or_else(|| build_err::zip_io(io::Error::other("zip writer was lost to an earlier error")))?;
+
+		let _permit = fs::blocking_permit().await;
+		let (writer, result) = ::tokio::task::spawn_blocking(move || {
+			let result = f(&mut writer);
+			(writer, result)
+		}).await.map_err(|e| build_err::zip_io(io::Error::other(e)))?;
+
+		self.writer = Some(writer);
+		Ok(result?)
+	}
+
+	/// Closes and removes the unfinished archive, if there is one. There's
+	/// nothing to do if removing it fails, so the error is ignored
+	async fn remove_temp(&mut self) {
+		if let Some(temp_path) = self.temp_path.take() {
+			// closed first, since windows can't remove open files
+			self.writer = None;
+			let _ = fs::remove_file(|| temp_path.clone()).await;
+		}
+	}
+
+	async fn finish_inner(&mut self) -> Result<(), build_err::WriteZip> {
+		self.with_writer(|writer| {
+			writer.finish()?.flush()?;
+			Ok(())
+		}).await?;
+
+		// closed before it's moved, since windows can't move open files
+		self.writer = None;
+		let temp_path = self.temp_path.clone().expect("invalid state");
+		fs::rename(|| temp_path.clone(), || self.path.clone()).await?;
+		self.temp_path = None;
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl Output for ZipOutput {
+	type Error = build_err::WriteZip;
+
+	async fn write_file(&mut self, path: &str, contents: &[u8]) -> Result<(), Self::Error> {
+		if !safe_path::is_normalised_relative(path) {
+			return Err(build_err::zip_invalid_path(path.into()))
+		}
+
+		let options = FileOptions::default()
+			.compression_method(CompressionMethod::Deflated)
+			.compression_level(None)
+			.last_modified_time(DateTime::default())
+			.unix_permissions(0o644);
+
+		let path = path.to_string();
+		let contents = contents.to_vec();
+		self.with_writer(move |writer| {
+			writer.start_file(path, options)?;
+			writer.write_all(&contents)?;
+			Ok(())
+		}).await
+	}
+
+	async fn finish(mut self) -> Result<(), Self::Error> {
+		let result = self.finish_inner().await;
+		if result.is_err() {
+			self.remove_temp().await;
+		}
+		result
+	}
+
+	#[inline]
+	async fn abort(mut self) {
+		self.remove_temp().await;
+	}
+}
+
+impl Drop for ZipOutput {
+	/// Only a fallback for when it's dropped without being finished or
+	/// aborted (ex. on panic), since this blocks whatever thread drops it
+	fn drop(&mut self) {
+		if let Some(temp_path) = self.temp_path.take() {
+			// closed first, since windows can't remove open files. there's
+			// nothing to do if removing it fails, so the error is ignored
+			drop(self.writer.take());
+			let _ = ::std::fs::remove_file(temp_path);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::build::{ Files, write_to };
+	use crate::test_util;
+	use ::std::io::Read as _;
+
+	#[tokio::test]
+	async fn zip_has_files_at_root() {
+		let scratch = test_util::scratch_dir("zip-output");
+		let path = scratch.join("pack.zip");
+		let path = path.to_str().unwrap();
+
+		let mut output = ZipOutput::new(path).await.unwrap();
+		output.write_file("pack.mcmeta", b"{}").await.unwrap();
+		output.write_file("assets/minecraft/models/block/stone.json", b"[]").await.unwrap();
+		output.finish().await.unwrap();
+
+		let file = ::std::fs::File::open(path).unwrap();
+		let mut archive = ::zip::ZipArchive::new(file).unwrap();
+
+		let mut contents = String::new();
+		archive.by_name("pack.mcmeta").unwrap().read_to_string(&mut contents).unwrap();
+		assert_eq!("{}", contents);
+		assert!(archive.by_name("assets/minecraft/models/block/stone.json").is_ok());
+		assert_eq!(2, archive.len());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn dir_refuses_paths_outside_it() {
+		let dir = test_util::scratch_dir("dir-output-paths");
+
+		let mut output = DirOutput::new(dir.join("out").to_str().unwrap()).await.unwrap();
+		output.write_file("assets/minecraft/a.png", b"").await.unwrap();
+		for path in ["../escaped", "/tmp/escaped", "assets/../../escaped"] {
+			let error = output.write_file(path, b"").await.unwrap_err();
+			assert!(matches!(error, build_err::WriteDir::InvalidPath { .. }));
+		}
+		assert!(!dir.join("escaped").exists());
+
+		::std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[tokio::test]
+	async fn zip_refuses_paths_outside_it() {
+		let scratch = test_util::scratch_dir("zip-output-paths");
+		let path = scratch.join("pack.zip");
+		let path = path.to_str().unwrap();
+
+		let mut output = ZipOutput::new(path).await.unwrap();
+		output.write_file("assets/minecraft/a.png", b"").await.unwrap();
+		for path in ["../escaped", "/tmp/escaped", "assets/../../escaped"] {
+			let error = output.write_file(path, b"").await.unwrap_err();
+			assert!(matches!(error, build_err::WriteZip::InvalidPath { .. }));
+		}
+
+		// nothing is left behind if it isn't finished
+		output.abort().await;
+		assert_eq!(0, ::std::fs::read_dir(&scratch).unwrap().count());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn zip_dropped_unfinished_is_removed() {
+		let scratch = test_util::scratch_dir("zip-output-dropped");
+		let path = scratch.join("pack.zip");
+		let path = path.to_str().unwrap();
+
+		let mut output = ZipOutput::new(path).await.unwrap();
+		output.write_file("pack.mcmeta", b"{}").await.unwrap();
+		drop(output);
+		assert_eq!(0, ::std::fs::read_dir(&scratch).unwrap().count());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn write_to_aborts_on_error() {
+		let scratch = test_util::scratch_dir("zip-output-aborted");
+		let path = scratch.join("pack.zip");
+		let path = path.to_str().unwrap();
+
+		let mut files = Files::new();
+		files.add_file("pack.mcmeta".into(), b"{}".to_vec()).unwrap();
+		files.add_file("../escaped".into(), Vec::new()).unwrap();
+
+		let error = write_to(&files, ZipOutput::new(path).await.unwrap()).await.unwrap_err();
+		assert!(matches!(error, build_err::WriteZip::InvalidPath { .. }));
+		assert_eq!(0, ::std::fs::read_dir(&scratch).unwrap().count());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn zip_is_reproducible() {
+		async fn build_zip(path: &str, files: &[(&str, &[u8])]) -> Vec<u8> {
+			let mut built = Files::new();
+			for (path, contents) in files {
+				built.add_file((*path).into(), contents.to_vec()).unwrap();
+			}
+
+			write_to(&built, ZipOutput::new(path).await.unwrap()).await.unwrap();
+			let bytes = ::std::fs::read(path).unwrap();
+			::std::fs::remove_file(path).unwrap();
+			bytes
+		}
+
+		let scratch = test_util::scratch_dir("zip-reproducible");
+		let path = scratch.join("pack.zip");
+		let path = path.to_str().unwrap();
+
+		let first = build_zip(path, &[
+			("pack.mcmeta", b"{}"),
+			("assets/minecraft/blockstates/stone.json", b"{}"),
+			("assets/minecraft/models/block/stone.json", b"{}")
+		]).await;
+
+		let second = build_zip(path, &[
+			("assets/minecraft/models/block/stone.json", b"{}"),
+			("pack.mcmeta", b"{}"),
+			("assets/minecraft/blockstates/stone.json", b"{}")
+		]).await;
+
+		assert_eq!(first, second);
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+}
@@ -0,0 +1,321 @@
+use super::fs_err;
+use ::ts_result::*;
+
+#[derive(Debug)]
+pub enum AddFile {
+	Duplicate {
+		path: String
+	},
+	Json {
+		path: String,
+		error: ::serde_json::Error
+	},
+	ReadSource {
+		path: String,
+		error: fs_err::Read
+	}
+}
+
+impl NiceErrorMessage for AddFile {
+	fn fmt(&self, f: &mut Formatter) {
+		use AddFile::*;
+		match self {
+			Duplicate { path } => {
+				f.write_line("more than one texture tried to write the same file");
+				f.with_indent(|f| {
+					f.write_str(path);
+				});
+			}
+			Json { path, error } => {
+				f.write_line_args(format_args!("error serialising json for {path}"));
+				f.with_indent(|f| {
+					f.write_args(format_args!("{error}"));
+				});
+			}
+			ReadSource { path, error } => {
+				f.write_line_args(format_args!("error reading {path} to copy into the pack"));
+				f.fmt_with_indent(error);
+			}
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum Build {
+	NoPackFormat {
+		mc_version: &'static str
+	},
+	UnknownPack {
+		pack_id: String
+	},
+	UnknownTexture {
+		pack_id: String,
+		texture_id: String
+	},
+	UnknownOption {
+		pack_id: String,
+		texture_id: String,
+		option_id: String
+	},
+	SelectedExtendingTexture {
+		pack_id: String,
+		texture_id: String,
+		extends_pack_id: String,
+		extends_texture_id: String
+	},
+	NoProviderAvailable {
+		pack_id: String,
+		texture_id: String,
+		option_id: String,
+		mc_version: &'static str
+	},
+	AddFile(AddFile)
+}
+
+impl From<AddFile> for Build {
+	fn from(error: AddFile) -> Self {
+		Self::AddFile(error)
+	}
+}
+
+impl NiceErrorMessage for Build {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("error building pack");
+
+		use Build::*;
+		f.with_indent(|f| match self {
+			NoPackFormat { mc_version } => {
+				f.write_args(format_args!("minecraft version {mc_version} does not have a known pack format"));
+			}
+			UnknownPack { pack_id } => {
+				f.write_args(format_args!("pack {pack_id} is not in the workspace"));
+			}
+			UnknownTexture { pack_id, texture_id } => {
+				f.write_args(format_args!("pack {pack_id} does not have texture {texture_id}"));
+			}
+			UnknownOption { pack_id, texture_id, option_id } => {
+				f.write_args(format_args!("texture {pack_id}:{texture_id} does not have option {option_id}"));
+			}
+			SelectedExtendingTexture { pack_id, texture_id, extends_pack_id, extends_texture_id } => {
+				f.write_args(format_args!(
+					"texture {pack_id}:{texture_id} extends {extends_pack_id}:{extends_texture_id}, so its options have to be selected through that texture"
+				));
+			}
+			NoProviderAvailable { pack_id, texture_id, option_id, mc_version } => {
+				f.write_args(format_args!(
+					"option {pack_id}:{texture_id}:{option_id} has no provider available for minecraft version {mc_version}"
+				));
+			}
+			AddFile(error) => {
+				f.fmt(error);
+			}
+		});
+	}
+}
+
+#[derive(Debug)]
+pub enum WriteDir {
+	NotEmpty {
+		path: String
+	},
+	InvalidPath {
+		path: String
+	},
+	CreateDirAll(fs_err::CreateDirAllWithPath),
+	ReadDir(fs_err::ReadDirWithPath),
+	ReadDirEntry(fs_err::ReadDirEntryWithPath),
+	Write(fs_err::WriteWithPath)
+}
+
+impl From<fs_err::CreateDirAllWithPath> for WriteDir {
+	fn from(error: fs_err::CreateDirAllWithPath) -> Self {
+		Self::CreateDirAll(error)
+	}
+}
+
+impl From<fs_err::ReadDirWithPath> for WriteDir {
+	fn from(error: fs_err::ReadDirWithPath) -> Self {
+		Self::ReadDir(error)
+	}
+}
+
+impl From<fs_err::ReadDirEntryWithPath> for WriteDir {
+	fn from(error: fs_err::ReadDirEntryWithPath) -> Self {
+		Self::ReadDirEntry(error)
+	}
+}
+
+impl From<fs_err::WriteWithPath> for WriteDir {
+	fn from(error: fs_err::WriteWithPath) -> Self {
+		Self::Write(error)
+	}
+}
+
+impl NiceErrorMessage for WriteDir {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("error writing built pack to directory");
+
+		use WriteDir::*;
+		f.with_indent(|f| match self {
+			NotEmpty { path } => {
+				f.write_args(format_args!("output directory is not empty: {path}"));
+			}
+			InvalidPath { path } => {
+				f.write_args(format_args!("refusing to write {path}, since it isn't a normalised relative path"));
+			}
+			CreateDirAll(error) => { f.fmt(error) }
+			ReadDir(error) => { f.fmt(error) }
+			ReadDirEntry(error) => { f.fmt(error) }
+			Write(error) => { f.fmt(error) }
+		});
+	}
+}
+
+#[derive(Debug)]
+pub enum WriteZip {
+	InvalidPath {
+		path: String
+	},
+	Zip(::zip::result::ZipError),
+	Write(fs_err::WriteWithPath),
+	Rename(fs_err::RenameWithPath)
+}
+
+impl From<::zip::result::ZipError> for WriteZip {
+	fn from(error: ::zip::result::ZipError) -> Self {
+		Self::Zip(error)
+	}
+}
+
+impl From<fs_err::WriteWithPath> for WriteZip {
+	fn from(error: fs_err::WriteWithPath) -> Self {
+		Self::Write(error)
+	}
+}
+
+impl From<fs_err::RenameWithPath> for WriteZip {
+	fn from(error: fs_err::RenameWithPath) -> Self {
+		Self::Rename(error)
+	}
+}
+
+impl NiceErrorMessage for WriteZip {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("error writing built pack to zip");
+
+		use WriteZip::*;
+		f.with_indent(|f| match self {
+			InvalidPath { path } => {
+				f.write_args(format_args!("refusing to write {path}, since it isn't a normalised relative path"));
+			}
+			Zip(error) => { f.write_args(format_args!("{error}")) }
+			Write(error) => { f.fmt(error) }
+			Rename(error) => { f.fmt(error) }
+		});
+	}
+}
+
+/// Error from building a pack and writing it to an [`Output`], where `E` is
+/// the error type of the output
+///
+/// [`Output`]: crate::build::Output
+#[derive(Debug)]
+pub enum BuildTo<E> {
+	Build(Build),
+	Output(E)
+}
+
+impl<E> From<Build> for BuildTo<E> {
+	fn from(error: Build) -> Self {
+		Self::Build(error)
+	}
+}
+
+impl<E: NiceErrorMessage> NiceErrorMessage for BuildTo<E> {
+	fn fmt(&self, f: &mut Formatter) {
+		use BuildTo::*;
+		match self {
+			Build(error) => { f.fmt(error) }
+			Output(error) => { f.fmt(error) }
+		}
+	}
+}
+
+pub(crate) fn duplicate_file(path: String) -> AddFile {
+	AddFile::Duplicate { path }
+}
+
+pub(crate) fn json(path: String, error: ::serde_json::Error) -> AddFile {
+	AddFile::Json { path, error }
+}
+
+pub(crate) fn read_source(path: String, error: fs_err::Read) -> AddFile {
+	AddFile::ReadSource { path, error }
+}
+
+pub(crate) fn no_pack_format(mc_version: &'static str) -> Build {
+	Build::NoPackFormat { mc_version }
+}
+
+pub(crate) fn unknown_pack(pack_id: &str) -> Build {
+	let pack_id = pack_id.into();
+	Build::UnknownPack { pack_id }
+}
+
+pub(crate) fn unknown_texture(pack_id: &str, texture_id: &str) -> Build {
+	let pack_id = pack_id.into();
+	let texture_id = texture_id.into();
+	Build::UnknownTexture { pack_id, texture_id }
+}
+
+pub(crate) fn unknown_option(pack_id: &str, texture_id: &str, option_id: &str) -> Build {
+	let pack_id = pack_id.into();
+	let texture_id = texture_id.into();
+	let option_id = option_id.into();
+	Build::UnknownOption { pack_id, texture_id, option_id }
+}
+
+pub(crate) fn selected_extending_texture(
+	pack_id: &str,
+	texture_id: &str,
+	extends_pack_id: &str,
+	extends_texture_id: &str
+) -> Build {
+	let pack_id = pack_id.into();
+	let texture_id = texture_id.into();
+	let extends_pack_id = extends_pack_id.into();
+	let extends_texture_id = extends_texture_id.into();
+	Build::SelectedExtendingTexture { pack_id, texture_id, extends_pack_id, extends_texture_id }
+}
+
+pub(crate) fn no_provider_available(
+	pack_id: &str,
+	texture_id: &str,
+	option_id: &str,
+	mc_version: &'static str
+) -> Build {
+	let pack_id = pack_id.into();
+	let texture_id = texture_id.into();
+	let option_id = option_id.into();
+	Build::NoProviderAvailable { pack_id, texture_id, option_id, mc_version }
+}
+
+pub(crate) fn not_empty(path: String) -> WriteDir {
+	WriteDir::NotEmpty { path }
+}
+
+pub(crate) fn invalid_path(path: String) -> WriteDir {
+	WriteDir::InvalidPath { path }
+}
+
+pub(crate) fn zip_invalid_path(path: String) -> WriteZip {
+	WriteZip::InvalidPath { path }
+}
+
+pub(crate) fn zip_io(error: ::std::io::Error) -> WriteZip {
+	WriteZip::Zip(error.into())
+}
+
+pub(crate) fn output<E>(error: E) -> BuildTo<E> {
+	BuildTo::Output(error)
+}
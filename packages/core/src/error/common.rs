@@ -23,3 +23,59 @@ impl<E: NiceErrorMessage> NiceErrorMessage for WithPath<E> {
 		});
 	}
 }
+
+/// An error from reading or writing a manifest, of any of the kinds of entry
+/// in a pack, kept as it is
+#[derive(Debug)]
+pub enum ManifestError {
+	Pack(crate::pack2::Error),
+	Texture(crate::texture2::Error),
+	Option(crate::option2::Error),
+	Provider(crate::provider2::Error)
+}
+
+impl From<crate::pack2::Error> for ManifestError {
+	fn from(error: crate::pack2::Error) -> Self {
+		Self::Pack(error)
+	}
+}
+
+impl From<crate::texture2::Error> for ManifestError {
+	fn from(error: crate::texture2::Error) -> Self {
+		Self::Texture(error)
+	}
+}
+
+impl From<crate::option2::Error> for ManifestError {
+	fn from(error: crate::option2::Error) -> Self {
+		Self::Option(error)
+	}
+}
+
+impl From<crate::provider2::Error> for ManifestError {
+	fn from(error: crate::provider2::Error) -> Self {
+		Self::Provider(error)
+	}
+}
+
+impl NiceErrorMessage for ManifestError {
+	fn fmt(&self, f: &mut Formatter) {
+		use ManifestError::*;
+		match self {
+			Pack(error) => { write_lines(f, &error.to_string()) }
+			Texture(error) => { write_lines(f, &error.to_string()) }
+			Option(error) => { write_lines(f, &error.to_string()) }
+			Provider(error) => { write_lines(f, &error.to_string()) }
+		}
+	}
+}
+
+/// Writes `s` a line at a time, so every line is indented (messages of errors
+/// from the *2 modules can have newlines in them)
+pub(crate) fn write_lines(f: &mut Formatter, s: &str) {
+	let mut lines = s.lines().peekable();
+	while let Some(line) = lines.next() {
+		f.write_str(line);
+		if lines.peek().is_some() { f.next_line() }
+	}
+}
@@ -0,0 +1,33 @@
+use ::std::error::Error;
+use ::ts_result::*;
+
+/// Packs that depend on each other in a cycle, so none of them can be loaded
+#[derive(Debug)]
+pub struct Cycle {
+	/// IDs of the packs in the cycle, in dependency order, starting and ending
+	/// with the same pack
+	path: Vec<String>
+}
+
+impl Cycle {
+	#[inline]
+	pub fn path(&self) -> &[String] {
+		&self.path
+	}
+}
+
+impl NiceErrorMessage for Cycle {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("packs depend on each other in a cycle (each pack depends on the one after it)");
+		f.with_indent(|f| {
+			f.write_str(&self.path.join(" -> "));
+		});
+	}
+}
+
+impl_display!(Cycle);
+impl Error for Cycle {}
+
+pub(crate) fn cycle(path: Vec<String>) -> Cycle {
+	Cycle { path }
+}
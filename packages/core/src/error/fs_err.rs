@@ -181,6 +181,136 @@ impl NiceErrorMessage for ReadDirEntry {
 	}
 }
 
+#[derive(Debug)]
+pub enum Write {
+	FileSystem {
+		error: ::std::io::Error
+	},
+	Join {
+		error: SpawnBlocking
+	}
+}
+
+pub type WriteWithPath = WithPath<Write>;
+
+impl NiceErrorMessage for Write {
+	fn fmt(&self, f: &mut Formatter) {
+		use Write::*;
+		match self {
+			FileSystem { error } => {
+				f.write_line("error writing file");
+				f.with_indent(|f| {
+					f.write_args(format_args!("{error}"));
+				});
+			}
+			Join { error } => {
+				f.write_line("error joining task while writing file");
+				f.with_indent(|f| {
+					f.fmt(error);
+				});
+			}
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum CreateDirAll {
+	FileSystem {
+		error: ::std::io::Error
+	},
+	Join {
+		error: SpawnBlocking
+	}
+}
+
+pub type CreateDirAllWithPath = WithPath<CreateDirAll>;
+
+impl NiceErrorMessage for CreateDirAll {
+	fn fmt(&self, f: &mut Formatter) {
+		use CreateDirAll::*;
+		match self {
+			FileSystem { error } => {
+				f.write_line("error creating directory");
+				f.with_indent(|f| {
+					f.write_args(format_args!("{error}"));
+				});
+			}
+			Join { error } => {
+				f.write_line("error joining task while creating directory");
+				f.with_indent(|f| {
+					f.fmt(error);
+				});
+			}
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum Rename {
+	FileSystem {
+		from: String,
+		error: ::std::io::Error
+	},
+	Join {
+		from: String,
+		error: SpawnBlocking
+	}
+}
+
+pub type RenameWithPath = WithPath<Rename>;
+
+impl NiceErrorMessage for Rename {
+	fn fmt(&self, f: &mut Formatter) {
+		use Rename::*;
+		match self {
+			FileSystem { from, error } => {
+				f.write_line_args(format_args!("error moving {from}"));
+				f.with_indent(|f| {
+					f.write_args(format_args!("{error}"));
+				});
+			}
+			Join { from, error } => {
+				f.write_line_args(format_args!("error joining task while moving {from}"));
+				f.with_indent(|f| {
+					f.fmt(error);
+				});
+			}
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum Remove {
+	FileSystem {
+		error: ::std::io::Error
+	},
+	Join {
+		error: SpawnBlocking
+	}
+}
+
+pub type RemoveWithPath = WithPath<Remove>;
+
+impl NiceErrorMessage for Remove {
+	fn fmt(&self, f: &mut Formatter) {
+		use Remove::*;
+		match self {
+			FileSystem { error } => {
+				f.write_line("error removing");
+				f.with_indent(|f| {
+					f.write_args(format_args!("{error}"));
+				});
+			}
+			Join { error } => {
+				f.write_line("error joining task while removing");
+				f.with_indent(|f| {
+					f.fmt(error);
+				});
+			}
+		}
+	}
+}
+
 pub fn spawn_blocking(error: ::tokio::task::JoinError) -> SpawnBlocking {
 	SpawnBlocking { error }
 }
@@ -226,3 +356,35 @@ pub fn read_dir_entry(error: ::std::io::Error, path: String) -> WithPath<ReadDir
 	let error = ReadDirEntry { error };
 	WithPath::new(error, path)
 }
+
+pub fn write_fs(error: ::std::io::Error) -> Write {
+	Write::FileSystem { error }
+}
+
+pub fn write_join(error: SpawnBlocking) -> Write {
+	Write::Join { error }
+}
+
+pub fn create_dir_all_fs(error: ::std::io::Error) -> CreateDirAll {
+	CreateDirAll::FileSystem { error }
+}
+
+pub fn create_dir_all_join(error: SpawnBlocking) -> CreateDirAll {
+	CreateDirAll::Join { error }
+}
+
+pub fn rename_fs(error: ::std::io::Error, from: String) -> Rename {
+	Rename::FileSystem { from, error }
+}
+
+pub fn rename_join(error: SpawnBlocking, from: String) -> Rename {
+	Rename::Join { from, error }
+}
+
+pub fn remove_fs(error: ::std::io::Error) -> Remove {
+	Remove::FileSystem { error }
+}
+
+pub fn remove_join(error: SpawnBlocking) -> Remove {
+	Remove::Join { error }
+}
@@ -0,0 +1,186 @@
+use super::{ fs_err, scaffold_err };
+use ::ts_result::*;
+
+#[derive(Debug)]
+pub enum ImportPack {
+	NoMcmeta {
+		source: String
+	},
+	Mcmeta {
+		error: ::serde_json::Error
+	},
+	UnknownPackFormat {
+		pack_format: u8
+	},
+	NonUtf8Path {
+		path: String
+	},
+	UnsafePath {
+		path: String
+	},
+	Symlink {
+		path: String
+	},
+	Read {
+		path: String,
+		error: fs_err::Read
+	},
+	Zip {
+		path: String,
+		error: ::zip::result::ZipError
+	},
+	ZipTooLarge {
+		path: String,
+		limit: u64
+	},
+	IsDir(fs_err::IsDir),
+	Exists(fs_err::MetadataWithPath),
+	ReadDir(fs_err::ReadDirWithPath),
+	ReadDirEntry(fs_err::ReadDirEntryWithPath),
+	CreateDirAll(fs_err::CreateDirAllWithPath),
+	Write(fs_err::WriteWithPath),
+	Rename(fs_err::RenameWithPath),
+	Remove(fs_err::RemoveWithPath),
+	Scaffold(scaffold_err::Scaffold)
+}
+
+impl From<fs_err::IsDir> for ImportPack {
+	fn from(error: fs_err::IsDir) -> Self {
+		Self::IsDir(error)
+	}
+}
+
+impl From<fs_err::MetadataWithPath> for ImportPack {
+	fn from(error: fs_err::MetadataWithPath) -> Self {
+		Self::Exists(error)
+	}
+}
+
+impl From<fs_err::ReadDirWithPath> for ImportPack {
+	fn from(error: fs_err::ReadDirWithPath) -> Self {
+		Self::ReadDir(error)
+	}
+}
+
+impl From<fs_err::ReadDirEntryWithPath> for ImportPack {
+	fn from(error: fs_err::ReadDirEntryWithPath) -> Self {
+		Self::ReadDirEntry(error)
+	}
+}
+
+impl From<fs_err::CreateDirAllWithPath> for ImportPack {
+	fn from(error: fs_err::CreateDirAllWithPath) -> Self {
+		Self::CreateDirAll(error)
+	}
+}
+
+impl From<fs_err::WriteWithPath> for ImportPack {
+	fn from(error: fs_err::WriteWithPath) -> Self {
+		Self::Write(error)
+	}
+}
+
+impl From<fs_err::RenameWithPath> for ImportPack {
+	fn from(error: fs_err::RenameWithPath) -> Self {
+		Self::Rename(error)
+	}
+}
+
+impl From<fs_err::RemoveWithPath> for ImportPack {
+	fn from(error: fs_err::RemoveWithPath) -> Self {
+		Self::Remove(error)
+	}
+}
+
+impl From<scaffold_err::Scaffold> for ImportPack {
+	fn from(error: scaffold_err::Scaffold) -> Self {
+		Self::Scaffold(error)
+	}
+}
+
+impl NiceErrorMessage for ImportPack {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("error importing resource pack");
+
+		use ImportPack::*;
+		f.with_indent(|f| match self {
+			NoMcmeta { source } => {
+				f.write_args(format_args!("{source} has no pack.mcmeta, so it isn't a resource pack"));
+			}
+			Mcmeta { error } => {
+				f.write_line("couldn't parse pack.mcmeta");
+				f.with_indent(|f| f.write_args(format_args!("{error}")));
+			}
+			UnknownPackFormat { pack_format } => {
+				f.write_args(format_args!("no known minecraft version uses pack format {pack_format}"));
+			}
+			NonUtf8Path { path } => {
+				f.write_args(format_args!("non UTF-8 paths are not supported (in {path})"));
+			}
+			UnsafePath { path } => {
+				f.write_args(format_args!("{path} isn't a relative path inside the resource pack"));
+			}
+			Symlink { path } => {
+				f.write_args(format_args!("{path} is a symlink, which can't be imported, since it could point outside the resource pack"));
+			}
+			Read { path, error } => {
+				f.write_line_args(format_args!("couldn't read {path}"));
+				f.fmt_with_indent(error);
+			}
+			Zip { path, error } => {
+				f.write_line_args(format_args!("couldn't read zip file {path}"));
+				f.with_indent(|f| f.write_args(format_args!("{error}")));
+			}
+			ZipTooLarge { path, limit } => {
+				f.write_args(format_args!("files in zip file {path} add up to more than {limit} bytes"));
+			}
+			IsDir(error) => { f.fmt(error) }
+			Exists(error) => { f.fmt(error) }
+			ReadDir(error) => { f.fmt(error) }
+			ReadDirEntry(error) => { f.fmt(error) }
+			CreateDirAll(error) => { f.fmt(error) }
+			Write(error) => { f.fmt(error) }
+			Rename(error) => { f.fmt(error) }
+			Remove(error) => { f.fmt(error) }
+			Scaffold(error) => { f.fmt(error) }
+		});
+	}
+}
+
+impl_display!(ImportPack);
+
+pub(crate) fn no_mcmeta(source: String) -> ImportPack {
+	ImportPack::NoMcmeta { source }
+}
+
+pub(crate) fn mcmeta(error: ::serde_json::Error) -> ImportPack {
+	ImportPack::Mcmeta { error }
+}
+
+pub(crate) fn unknown_pack_format(pack_format: u8) -> ImportPack {
+	ImportPack::UnknownPackFormat { pack_format }
+}
+
+pub(crate) fn non_utf8_path(path: String) -> ImportPack {
+	ImportPack::NonUtf8Path { path }
+}
+
+pub(crate) fn unsafe_path(path: String) -> ImportPack {
+	ImportPack::UnsafePath { path }
+}
+
+pub(crate) fn symlink(path: String) -> ImportPack {
+	ImportPack::Symlink { path }
+}
+
+pub(crate) fn read(path: String, error: fs_err::Read) -> ImportPack {
+	ImportPack::Read { path, error }
+}
+
+pub(crate) fn zip(path: String, error: ::zip::result::ZipError) -> ImportPack {
+	ImportPack::Zip { path, error }
+}
+
+pub(crate) fn zip_too_large(path: String, limit: u64) -> ImportPack {
+	ImportPack::ZipTooLarge { path, limit }
+}
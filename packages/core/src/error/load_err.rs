@@ -0,0 +1,108 @@
+use super::common::write_lines;
+use ::serde::Serialize;
+use ::std::error::Error;
+use ::std::fmt::Display;
+use ::ts_result::*;
+
+/// A problem found while loading a pack, along with the path of the texture,
+/// option or provider it was found in. In lenient mode, things that fail to
+/// load are left out of the pack, and the rest of it is still loaded.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostic {
+	severity: Severity,
+	path: String,
+	message: String
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	/// the thing it was found in couldn't be loaded
+	Error,
+	/// loading recovered from it (only in lenient mode)
+	Warning
+}
+
+impl Diagnostic {
+	#[inline]
+	pub fn severity(&self) -> Severity {
+		self.severity
+	}
+
+	#[inline]
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	#[inline]
+	pub fn message(&self) -> &str {
+		&self.message
+	}
+}
+
+impl NiceErrorMessage for Diagnostic {
+	fn fmt(&self, f: &mut Formatter) {
+		let severity = match self.severity {
+			Severity::Error => { "error" }
+			Severity::Warning => { "warning" }
+		};
+		f.write_line_args(format_args!("{severity} in {}", self.path));
+
+		f.with_indent(|f| write_lines(f, &self.message));
+	}
+}
+
+impl_display!(Diagnostic);
+
+/// A pack had errors in (some of) its textures, options or providers, in
+/// strict mode. Has every problem that was found, not just the first one.
+#[derive(Debug)]
+pub struct LoadFailed {
+	diagnostics: Vec<Diagnostic>
+}
+
+impl LoadFailed {
+	#[inline]
+	pub fn diagnostics(&self) -> &[Diagnostic] {
+		&self.diagnostics
+	}
+}
+
+impl NiceErrorMessage for LoadFailed {
+	fn fmt(&self, f: &mut Formatter) {
+		let errors = self.diagnostics.iter()
+			.filter(|d| d.severity == Severity::Error)
+			.count();
+		f.write_line_args(format_args!(
+			"pack failed to load ({errors} error{})",
+			if errors == 1 { "" } else { "s" }
+		));
+
+		f.with_indent(|f| {
+			let mut diagnostics = self.diagnostics.iter().peekable();
+			while let Some(d) = diagnostics.next() {
+				f.fmt(d);
+				if diagnostics.peek().is_some() { f.next_line() }
+			}
+		});
+	}
+}
+
+impl_display!(LoadFailed);
+impl Error for LoadFailed {}
+
+pub(crate) fn error<E: Display>(path: String, error: E) -> Diagnostic {
+	let severity = Severity::Error;
+	let message = error.to_string();
+	Diagnostic { severity, path, message }
+}
+
+pub(crate) fn warning<E: Display>(path: String, error: E) -> Diagnostic {
+	let severity = Severity::Warning;
+	let message = error.to_string();
+	Diagnostic { severity, path, message }
+}
+
+pub(crate) fn load_failed(diagnostics: Vec<Diagnostic>) -> LoadFailed {
+	LoadFailed { diagnostics }
+}
@@ -0,0 +1,99 @@
+use super::common::ManifestError;
+use super::fs_err;
+use crate::util::error as util_err;
+use ::ts_result::*;
+
+#[derive(Debug)]
+pub enum UpgradeSource {
+	NonUtf8Path {
+		path: String
+	},
+	Manifest {
+		path: String,
+		error: Box<ManifestError>
+	},
+	Read {
+		path: String,
+		error: fs_err::ReadToString
+	},
+	ReadDir {
+		path: String,
+		error: util_err::Error
+	},
+	IsDir(fs_err::IsDir),
+	IsFile(fs_err::IsFile),
+	Write(fs_err::WriteWithPath),
+	Rename(fs_err::RenameWithPath)
+}
+
+impl From<fs_err::IsDir> for UpgradeSource {
+	fn from(error: fs_err::IsDir) -> Self {
+		Self::IsDir(error)
+	}
+}
+
+impl From<fs_err::IsFile> for UpgradeSource {
+	fn from(error: fs_err::IsFile) -> Self {
+		Self::IsFile(error)
+	}
+}
+
+impl From<fs_err::WriteWithPath> for UpgradeSource {
+	fn from(error: fs_err::WriteWithPath) -> Self {
+		Self::Write(error)
+	}
+}
+
+impl From<fs_err::RenameWithPath> for UpgradeSource {
+	fn from(error: fs_err::RenameWithPath) -> Self {
+		Self::Rename(error)
+	}
+}
+
+impl NiceErrorMessage for UpgradeSource {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("error upgrading pack source");
+
+		use UpgradeSource::*;
+		f.with_indent(|f| match self {
+			NonUtf8Path { path } => {
+				f.write_args(format_args!("non UTF-8 paths are not supported (in {path})"));
+			}
+			Manifest { path, error } => {
+				f.write_line_args(format_args!("couldn't upgrade manifest {path}"));
+				f.fmt_with_indent(&**error);
+			}
+			Read { path, error } => {
+				f.write_line_args(format_args!("couldn't read manifest {path}"));
+				f.fmt_with_indent(error);
+			}
+			ReadDir { path, error } => {
+				f.write_line_args(format_args!("couldn't read directory {path}"));
+				f.with_indent(|f| f.write_args(format_args!("{error}")));
+			}
+			IsDir(error) => { f.fmt(error) }
+			IsFile(error) => { f.fmt(error) }
+			Write(error) => { f.fmt(error) }
+			Rename(error) => { f.fmt(error) }
+		});
+	}
+}
+
+impl_display!(UpgradeSource);
+
+pub(crate) fn non_utf8_path(path: String) -> UpgradeSource {
+	UpgradeSource::NonUtf8Path { path }
+}
+
+pub(crate) fn manifest<E: Into<ManifestError>>(path: String, error: E) -> UpgradeSource {
+	let error = Box::new(error.into());
+	UpgradeSource::Manifest { path, error }
+}
+
+pub(crate) fn read(path: String, error: fs_err::ReadToString) -> UpgradeSource {
+	UpgradeSource::Read { path, error }
+}
+
+pub(crate) fn read_dir(path: String, error: util_err::Error) -> UpgradeSource {
+	UpgradeSource::ReadDir { path, error }
+}
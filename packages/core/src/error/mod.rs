@@ -1,6 +1,14 @@
+pub mod build_err;
 pub mod common;
+pub mod dependency_err;
 pub mod fs_err;
+pub mod import_err;
+pub mod load_err;
+pub mod migrate_err;
 pub mod path_builder_err;
 pub mod provider_err;
 pub mod ron_err;
+pub mod scaffold_err;
 pub mod utf8_err;
+pub mod validate_err;
+pub mod watch_err;
@@ -0,0 +1,99 @@
+use super::common::ManifestError;
+use super::fs_err;
+use ::ts_result::*;
+
+#[derive(Debug)]
+pub enum Scaffold {
+	InvalidID {
+		kind: &'static str,
+		id: String,
+		invalid_chars: Vec<char>
+	},
+	AlreadyExists {
+		path: String
+	},
+	ParentNotFound {
+		kind: &'static str,
+		manifest: String
+	},
+	Manifest {
+		path: String,
+		error: Box<ManifestError>
+	},
+	Exists(fs_err::MetadataWithPath),
+	CreateDirAll(fs_err::CreateDirAllWithPath),
+	Write(fs_err::WriteWithPath)
+}
+
+impl From<fs_err::MetadataWithPath> for Scaffold {
+	fn from(error: fs_err::MetadataWithPath) -> Self {
+		Self::Exists(error)
+	}
+}
+
+impl From<fs_err::CreateDirAllWithPath> for Scaffold {
+	fn from(error: fs_err::CreateDirAllWithPath) -> Self {
+		Self::CreateDirAll(error)
+	}
+}
+
+impl From<fs_err::WriteWithPath> for Scaffold {
+	fn from(error: fs_err::WriteWithPath) -> Self {
+		Self::Write(error)
+	}
+}
+
+impl NiceErrorMessage for Scaffold {
+	fn fmt(&self, f: &mut Formatter) {
+		use Scaffold::*;
+		match self {
+			InvalidID { kind, id, invalid_chars } if invalid_chars.is_empty() => {
+				f.write_args(format_args!("{kind} ID cannot be empty (got \"{id}\")"));
+			}
+			InvalidID { kind, id, invalid_chars } => {
+				f.write_line_args(format_args!("{kind} ID \"{id}\" is invalid"));
+				f.with_indent(|f| {
+					f.write_line("invalid characters found:");
+					f.with_indent(|f| {
+						for c in invalid_chars {
+							f.write_line_args(format_args!("- '{c}'"));
+						}
+					});
+					f.write_str("IDs are only allowed to contain lowercase letters, digits and underscores");
+				});
+			}
+			AlreadyExists { path } => {
+				f.write_args(format_args!("{path} already exists, refusing to overwrite it"));
+			}
+			ParentNotFound { kind, manifest } => {
+				f.write_args(format_args!("couldn't find the {kind} to create it in ({manifest} doesn't exist)"));
+			}
+			Manifest { path, error } => {
+				f.write_line_args(format_args!("couldn't write manifest {path}"));
+				f.fmt_with_indent(&**error);
+			}
+			Exists(error) => { f.fmt(error) }
+			CreateDirAll(error) => { f.fmt(error) }
+			Write(error) => { f.fmt(error) }
+		}
+	}
+}
+
+impl_display!(Scaffold);
+
+pub(crate) fn invalid_id(kind: &'static str, id: String, invalid_chars: Vec<char>) -> Scaffold {
+	Scaffold::InvalidID { kind, id, invalid_chars }
+}
+
+pub(crate) fn already_exists(path: String) -> Scaffold {
+	Scaffold::AlreadyExists { path }
+}
+
+pub(crate) fn parent_not_found(kind: &'static str, manifest: String) -> Scaffold {
+	Scaffold::ParentNotFound { kind, manifest }
+}
+
+pub(crate) fn manifest<E: Into<ManifestError>>(path: String, error: E) -> Scaffold {
+	let error = Box::new(error.into());
+	Scaffold::Manifest { path, error }
+}
@@ -0,0 +1,99 @@
+use super::fs_err;
+use ::ts_result::*;
+
+/// A structural problem in a pack source, that loading doesn't report clearly,
+/// or at all
+#[derive(Debug)]
+pub enum Problem {
+	MissingManifest {
+		manifest: &'static str
+	},
+	UnreferencedFile,
+	InvalidAssetPath {
+		asset_path: String
+	}
+}
+
+impl NiceErrorMessage for Problem {
+	fn fmt(&self, f: &mut Formatter) {
+		use Problem::*;
+		match self {
+			MissingManifest { manifest } => {
+				f.write_args(format_args!("dir has no {manifest}, so it can't be loaded"));
+			}
+			UnreferencedFile => {
+				f.write_str("file isn't used by anything in the pack");
+			}
+			InvalidAssetPath { asset_path } => {
+				f.write_line_args(format_args!("file is copied to {asset_path}, which minecraft can't load"));
+				f.with_indent(|f| {
+					f.write_str("asset paths can only have lowercase letters, digits, `_`, `-`, `.` and `/` in them");
+				});
+			}
+		}
+	}
+}
+
+impl_display!(Problem);
+
+pub(crate) fn missing_manifest(manifest: &'static str) -> Problem {
+	Problem::MissingManifest { manifest }
+}
+
+pub(crate) fn unreferenced_file() -> Problem {
+	Problem::UnreferencedFile
+}
+
+pub(crate) fn invalid_asset_path(asset_path: String) -> Problem {
+	Problem::InvalidAssetPath { asset_path }
+}
+
+#[derive(Debug)]
+pub enum ValidatePack {
+	NonUtf8Path {
+		path: String
+	},
+	IsDir(fs_err::IsDir),
+	ReadDir(fs_err::ReadDirWithPath),
+	ReadDirEntry(fs_err::ReadDirEntryWithPath)
+}
+
+impl From<fs_err::IsDir> for ValidatePack {
+	fn from(error: fs_err::IsDir) -> Self {
+		Self::IsDir(error)
+	}
+}
+
+impl From<fs_err::ReadDirWithPath> for ValidatePack {
+	fn from(error: fs_err::ReadDirWithPath) -> Self {
+		Self::ReadDir(error)
+	}
+}
+
+impl From<fs_err::ReadDirEntryWithPath> for ValidatePack {
+	fn from(error: fs_err::ReadDirEntryWithPath) -> Self {
+		Self::ReadDirEntry(error)
+	}
+}
+
+impl NiceErrorMessage for ValidatePack {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("error validating pack source");
+
+		use ValidatePack::*;
+		f.with_indent(|f| match self {
+			NonUtf8Path { path } => {
+				f.write_args(format_args!("non UTF-8 paths are not supported (in {path})"));
+			}
+			IsDir(error) => { f.fmt(error) }
+			ReadDir(error) => { f.fmt(error) }
+			ReadDirEntry(error) => { f.fmt(error) }
+		});
+	}
+}
+
+impl_display!(ValidatePack);
+
+pub(crate) fn non_utf8_path(path: String) -> ValidatePack {
+	ValidatePack::NonUtf8Path { path }
+}
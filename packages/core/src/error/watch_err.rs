@@ -0,0 +1,40 @@
+use ::ts_result::*;
+
+#[derive(Debug)]
+pub enum Watch {
+	Create {
+		error: ::notify::Error
+	},
+	WatchDir {
+		path: String,
+		error: ::notify::Error
+	}
+}
+
+impl NiceErrorMessage for Watch {
+	fn fmt(&self, f: &mut Formatter) {
+		f.write_line("error watching for file changes");
+
+		use Watch::*;
+		f.with_indent(|f| match self {
+			Create { error } => {
+				f.write_line("couldn't start watching");
+				f.with_indent(|f| f.write_args(format_args!("{error}")));
+			}
+			WatchDir { path, error } => {
+				f.write_line_args(format_args!("couldn't watch {path}"));
+				f.with_indent(|f| f.write_args(format_args!("{error}")));
+			}
+		});
+	}
+}
+
+impl_display!(Watch);
+
+pub(crate) fn create(error: ::notify::Error) -> Watch {
+	Watch::Create { error }
+}
+
+pub(crate) fn watch(path: String, error: ::notify::Error) -> Watch {
+	Watch::WatchDir { path, error }
+}
@@ -0,0 +1,359 @@
+//! Shared bits for generators that output blockstates and block models
+
+use ::mc_versions::MCVersionRef;
+use ::schemars::JsonSchema;
+use ::schemars::gen::SchemaGenerator;
+use ::schemars::schema::{ InstanceType, Schema, SchemaObject };
+use ::serde::{ Deserialize, Serialize };
+use ::std::collections::BTreeMap;
+use ::wiwipaccer_common::{ MinecraftID, MinecraftIDError };
+
+/// The pack format of 1.13, where blocks were flattened and lots of asset
+/// paths changed
+const FLATTENING_PACK_FORMAT: u8 = 4;
+
+/// A block ID, validated when deserialising. Accepts `namespace:id` or
+/// just `id` (namespace defaults to `minecraft`).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BlockID {
+	inner: MinecraftID
+}
+
+impl TryFrom<String> for BlockID {
+	type Error = MinecraftIDError;
+
+	#[inline]
+	fn try_from(value: String) -> Result<Self, Self::Error> {
+		let inner = value.parse()?;
+		Ok(Self { inner })
+	}
+}
+
+impl From<BlockID> for String {
+	#[inline]
+	fn from(value: BlockID) -> Self {
+		value.inner.to_string()
+	}
+}
+
+// the rest of these are written by hand, since schemars doesn't know about
+// `try_from`, and would describe the private fields instead
+
+impl JsonSchema for BlockID {
+	fn schema_name() -> String {
+		"BlockID".into()
+	}
+
+	fn json_schema(_: &mut SchemaGenerator) -> Schema {
+		let mut schema = SchemaObject {
+			instance_type: Some(InstanceType::String.into()),
+			..Default::default()
+		};
+		schema.string().pattern = Some("^([a-z0-9_]*:)?[a-z0-9_]*$".into());
+		schema.metadata().description = Some("`namespace:id`, or just `id` (namespace defaults to `minecraft`)".into());
+		schema.into()
+	}
+}
+
+/// A rotation of a blockstate variant, one of 0, 90, 180, or 270,
+/// validated when deserialising
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub struct Rotation {
+	degrees: u16
+}
+
+impl TryFrom<u16> for Rotation {
+	type Error = String;
+
+	#[inline]
+	fn try_from(degrees: u16) -> Result<Self, Self::Error> {
+		match degrees {
+			0 | 90 | 180 | 270 => { Ok(Self { degrees }) }
+			_ => { Err(format!("rotation must be one of 0, 90, 180, or 270, got {degrees}")) }
+		}
+	}
+}
+
+impl From<Rotation> for u16 {
+	#[inline]
+	fn from(value: Rotation) -> Self {
+		value.degrees
+	}
+}
+
+impl JsonSchema for Rotation {
+	fn schema_name() -> String {
+		"Rotation".into()
+	}
+
+	fn json_schema(_: &mut SchemaGenerator) -> Schema {
+		SchemaObject {
+			instance_type: Some(InstanceType::Integer.into()),
+			enum_values: Some(vec![0.into(), 90.into(), 180.into(), 270.into()]),
+			..Default::default()
+		}.into()
+	}
+}
+
+impl Rotation {
+	/// Rotation in degrees, or `None` if it's 0, so it can be left out of
+	/// the output
+	#[inline]
+	pub(super) fn non_zero(rotation: Option<Self>) -> Option<u16> {
+		rotation
+			.map(|r| r.degrees)
+			.filter(|d| *d != 0)
+	}
+
+	/// All four rotations, with no rotation as `None`
+	#[inline]
+	pub(super) fn all() -> Vec<Option<Self>> {
+		[None, Some(90), Some(180), Some(270)]
+			.into_iter()
+			.map(|d| d.map(|degrees| Self { degrees }))
+			.collect()
+	}
+}
+
+/// Weight of a blockstate variant, relative to the other variants in the same
+/// list. Has to be at least 1, validated when deserialising
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct Weight {
+	weight: u32
+}
+
+impl TryFrom<u32> for Weight {
+	type Error = &'static str;
+
+	#[inline]
+	fn try_from(weight: u32) -> Result<Self, Self::Error> {
+		match weight {
+			0 => { Err("weight must be at least 1") }
+			_ => { Ok(Self { weight }) }
+		}
+	}
+}
+
+impl From<Weight> for u32 {
+	#[inline]
+	fn from(value: Weight) -> Self {
+		value.weight
+	}
+}
+
+impl JsonSchema for Weight {
+	fn schema_name() -> String {
+		"Weight".into()
+	}
+
+	fn json_schema(_: &mut SchemaGenerator) -> Schema {
+		let mut schema = SchemaObject {
+			instance_type: Some(InstanceType::Integer.into()),
+			..Default::default()
+		};
+		schema.number().minimum = Some(1.0);
+		schema.into()
+	}
+}
+
+/// One entry in a list of random variants, with everything vanilla blockstate
+/// variant arrays support (except the model, which the generator decides)
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Eq, Serialize)]
+pub struct VariantSpec {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	x: Option<Rotation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	y: Option<Rotation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	uvlock: Option<bool>,
+	/// defaults to 1 (in game)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	weight: Option<Weight>
+}
+
+impl VariantSpec {
+	#[inline]
+	pub(super) fn to_variant(&self, model: String) -> Variant {
+		Variant {
+			model,
+			x: Rotation::non_zero(self.x),
+			y: Rotation::non_zero(self.y),
+			uvlock: self.uvlock,
+			weight: self.weight.map(u32::from)
+		}
+	}
+}
+
+/// Which variants a random block generator picks from. Either `y`, a plain
+/// list of y rotations (`None` meaning not rotated), or `variants`, a list of
+/// [`VariantSpec`]s for full control, can be provided, but not both. If
+/// neither are provided, it defaults to all four y rotations, equally weighted.
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(try_from = "RandomVariantsMeta")]
+pub struct RandomVariants {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	y: Option<Vec<Option<Rotation>>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	variants: Option<Vec<VariantSpec>>
+}
+
+#[derive(Deserialize)]
+struct RandomVariantsMeta {
+	y: Option<Vec<Option<Rotation>>>,
+	variants: Option<Vec<VariantSpec>>
+}
+
+impl TryFrom<RandomVariantsMeta> for RandomVariants {
+	type Error = &'static str;
+
+	fn try_from(meta: RandomVariantsMeta) -> Result<Self, Self::Error> {
+		let RandomVariantsMeta { y, variants } = meta;
+		match (&y, &variants) {
+			(Some(_), Some(_)) => { Err("only one of `y` and `variants` can be provided") }
+			(Some(y), _) if y.is_empty() => { Err("`y` cannot be empty") }
+			(_, Some(variants)) if variants.is_empty() => { Err("`variants` cannot be empty") }
+			_ => { Ok(Self { y, variants }) }
+		}
+	}
+}
+
+impl RandomVariants {
+	pub(super) fn specs(&self) -> Vec<VariantSpec> {
+		if let Some(variants) = &self.variants { return variants.clone() }
+
+		self.y.clone()
+			.unwrap_or_else(Rotation::all)
+			.into_iter()
+			.map(|y| VariantSpec { y, ..Default::default() })
+			.collect()
+	}
+}
+
+/// Generators only work for versions where we know the pack format, since we
+/// need it to know which paths to use (and the pack can't be built without it
+/// anyways)
+#[inline]
+pub(super) fn is_available_for(mc_version: MCVersionRef) -> bool {
+	mc_version.pack_format.get_version().is_some()
+}
+
+/// Paths to files, and references to other resources, for a block, in the
+/// format the provided version expects
+pub(super) struct BlockPaths<'h> {
+	ns: &'h str,
+	id: &'h str,
+	flattened: bool
+}
+
+impl<'h> BlockPaths<'h> {
+	#[inline]
+	pub(super) fn new(block_id: &'h BlockID, mc_version: MCVersionRef) -> Self {
+		let ns = block_id.inner.ns_ref();
+		let id = block_id.inner.id_ref();
+		let flattened = mc_version.pack_format
+			.get_version()
+			.map(|v| v >= FLATTENING_PACK_FORMAT)
+			.unwrap_or(true);
+		Self { ns, id, flattened }
+	}
+
+	#[inline]
+	pub(super) fn id(&self) -> &'h str {
+		self.id
+	}
+
+	#[inline]
+	pub(super) fn blockstate_path(&self) -> String {
+		let Self { ns, id, .. } = self;
+		format!("assets/{ns}/blockstates/{id}.json")
+	}
+
+	#[inline]
+	pub(super) fn model_path(&self, model: &str) -> String {
+		let ns = self.ns;
+		format!("assets/{ns}/models/block/{model}.json")
+	}
+
+	/// How a blockstate refers to a model in this block's namespace
+	#[inline]
+	pub(super) fn model_ref(&self, model: &str) -> String {
+		let ns = self.ns;
+		if self.flattened {
+			format!("{ns}:block/{model}")
+		} else {
+			// before 1.13, blockstates refer to models relative to models/block
+			format!("{ns}:{model}")
+		}
+	}
+
+	/// How a model refers to one of the builtin models (ex. `cube_all`)
+	#[inline]
+	pub(super) fn builtin_parent_ref(&self, parent: &str) -> String {
+		if self.flattened {
+			format!("minecraft:block/{parent}")
+		} else {
+			format!("block/{parent}")
+		}
+	}
+
+	/// How a model refers to a texture in this block's namespace
+	#[inline]
+	pub(super) fn texture_ref(&self, texture: &str) -> String {
+		let ns = self.ns;
+		if self.flattened {
+			format!("{ns}:block/{texture}")
+		} else {
+			format!("{ns}:blocks/{texture}")
+		}
+	}
+
+	/// Variant name for blocks that don't have any block state properties
+	#[inline]
+	pub(super) fn no_properties_variant(&self) -> &'static str {
+		if self.flattened { "" } else { "normal" }
+	}
+}
+
+#[derive(Serialize)]
+pub(super) struct Blockstate {
+	variants: BTreeMap<&'static str, Vec<Variant>>
+}
+
+impl Blockstate {
+	#[inline]
+	pub(super) fn single(variant: &'static str, models: Vec<Variant>) -> Self {
+		let mut variants = BTreeMap::new();
+		variants.insert(variant, models);
+		Self { variants }
+	}
+}
+
+#[derive(Serialize)]
+pub(super) struct Variant {
+	model: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	x: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	y: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	uvlock: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	weight: Option<u32>
+}
+
+#[derive(Serialize)]
+pub(super) struct Model {
+	parent: String,
+	textures: BTreeMap<&'static str, String>
+}
+
+impl Model {
+	#[inline]
+	pub(super) fn new(parent: String, textures: &[(&'static str, String)]) -> Self {
+		let textures = textures.iter().cloned().collect();
+		Self { parent, textures }
+	}
+}
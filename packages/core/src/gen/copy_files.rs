@@ -0,0 +1,326 @@
+use crate::build::Files;
+use crate::error::build_err;
+use crate::load::LoadContext;
+use crate::util::error::*;
+use crate::util::fs;
+use crate::util::fs2;
+use crate::util::safe_path;
+use ::camino::Utf8PathBuf;
+use ::hashbrown::HashSet;
+use ::mc_versions::MCVersionRef;
+use ::schemars::JsonSchema;
+use ::serde::{ Deserialize, Serialize };
+use ::wiwipaccer_common::mc_id::DEFAULT_NAMESPACE;
+
+pub(crate) const DEFAULT_DIR: &str = "files";
+
+/// Copies a directory of hand-authored files (textures, models, ...) from the
+/// provider dir into `assets/<namespace>/<path>` in the pack, as they are.
+///
+/// The files are found when the provider is loaded, so a missing dir is
+/// reported then, rather than partway through a build. They're only read when
+/// building.
+#[derive(Debug, Default, Deserialize, JsonSchema, Serialize)]
+pub struct CopyFiles {
+	/// dir (relative to the provider dir) to copy. Defaults to `files`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	dir: Option<String>,
+	/// namespace to copy the files into. Defaults to `minecraft`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	namespace: Option<String>,
+	/// path inside the namespace (ex. `textures/block`) to copy the files
+	/// into. Defaults to the root of the namespace
+	#[serde(skip_serializing_if = "Option::is_none")]
+	path: Option<String>,
+	/// the dir to copy, found on load
+	#[serde(skip)]
+	root: Utf8PathBuf,
+	/// files that were found on load, with paths relative to `root`
+	#[serde(skip)]
+	files: Vec<String>
+}
+
+impl CopyFiles {
+	/// Copies the files in the default dir to `path` in `namespace`
+	pub(crate) fn with_target(namespace: Option<String>, path: Option<String>) -> Self {
+		Self { namespace, path, ..Self::default() }
+	}
+
+	/// Finds all the files to copy, in the provided provider dir. Dirs and
+	/// entries in them that can't be read are recorded in `ctx` and skipped.
+	/// Symlinks are followed, but a dir is only copied once, so links back to
+	/// a dir they're in are recorded and skipped too.
+	pub(crate) async fn load(&mut self, provider_dir: &str, ctx: &mut LoadContext) -> Result<()> {
+		self.check_paths()?;
+
+		let mut root = Utf8PathBuf::from(provider_dir);
+		root.push(self.dir());
+
+		if !fs::is_dir2(root.as_str().into()).await? {
+			let path = root.into_string();
+			let path_name = "provider files dir".into();
+			return Err(Error::PathIsNotDir { path, path_name })
+		}
+
+		// dirs still to read, relative to root ("" being root itself)
+		let mut dirs = vec![String::new()];
+		let mut files = Vec::new();
+		// canonical paths of dirs read so far
+		let mut read_dirs = HashSet::new();
+
+		while let Some(dir) = dirs.pop() {
+			let dir_path = root.join(&dir).into_string();
+			let canonical = match fs::canonicalize2(dir_path.clone()).await {
+				Ok(c) => { c }
+				Err(e) => {
+					ctx.error(dir_path, e);
+					continue
+				}
+			};
+			if !read_dirs.insert(canonical) {
+				ctx.error(dir_path.clone(), Error::DirFoundTwice { path: dir_path });
+				continue
+			}
+
+			let mut read_dir = match fs::read_dir2(dir_path.clone()).await {
+				Ok(r) => { r }
+				Err(e) => {
+					ctx.error(dir_path, e);
+					continue
+				}
+			};
+
+			loop {
+				let entry = match read_dir.next().await {
+					Ok(Some(entry)) => { entry }
+					Ok(None) => { break }
+					Err(e) => {
+						ctx.error(dir_path.clone(), e);
+						break
+					}
+				};
+
+				let file_name = entry.file_name();
+				let file_name = match file_name.to_str() {
+					Some(f) => { f }
+					None => {
+						ctx.error(entry.path().to_string_lossy().into_owned(), Error::NonUtf8Path);
+						continue
+					}
+				};
+				let relative = match &*dir {
+					"" => { file_name.to_string() }
+					dir => { format!("{dir}/{file_name}") }
+				};
+
+				let path = root.join(&relative).into_string();
+				let file_type = match fs::file_type2(&entry).await {
+					Ok(t) => { t }
+					Err(e) => {
+						ctx.error(path, e);
+						continue
+					}
+				};
+				// the entry's own type is the link's, not what it links to
+				let is_dir = match file_type.is_symlink() {
+					true => match fs::metadata2(path.clone()).await {
+						Ok(m) => { m.is_dir() }
+						Err(e) => {
+							ctx.error(path, e);
+							continue
+						}
+					},
+					false => { file_type.is_dir() }
+				};
+				match is_dir {
+					true => { dirs.push(relative) }
+					false => { files.push(relative) }
+				}
+			}
+		}
+
+		files.sort_unstable();
+		self.root = root;
+		self.files = files;
+		Ok(())
+	}
+
+	/// The files are copied as they are, so they work in any version, as long
+	/// as it's one with a known pack format (same as the block generators)
+	#[inline]
+	pub fn is_available_for(&self, mc_version: MCVersionRef) -> bool {
+		super::block::is_available_for(mc_version)
+	}
+
+	/// Reads the files found on load, and adds them to the pack
+	pub async fn generate(&self, mc_version: MCVersionRef, files: &mut Files)
+		-> Result<(), build_err::AddFile>
+	{
+		let prefix = self.prefix();
+		for path in &self.files {
+			let source = self.root.join(path).into_string();
+			let contents = fs2::read(|| source.clone())
+				.await
+				.map_err(|e| build_err::read_source(source, e))?;
+			files.add_file(format!("{prefix}/{path}"), contents)?;
+		}
+
+		Ok(())
+	}
+
+	/// Files that are copied, as their path relative to the provider dir, and
+	/// the path they're copied to in the pack
+	pub(crate) fn copies(&self) -> Vec<(String, String)> {
+		let dir = self.dir();
+		let prefix = self.prefix();
+		self.files.iter()
+			.map(|path| (format!("{dir}/{path}"), format!("{prefix}/{path}")))
+			.collect()
+	}
+
+	/// `dir` is joined onto the provider dir, and `namespace` and `path` onto
+	/// the output dir when building, so none of them can be allowed to leave
+	/// those dirs
+	fn check_paths(&self) -> Result<()> {
+		let invalid = |path: &str, path_name: &str, expected| Error::InvalidPath {
+			path: path.into(),
+			path_name: path_name.into(),
+			expected
+		};
+
+		if !safe_path::is_normalised_relative(self.dir()) {
+			return Err(invalid(self.dir(), "provider files dir", "a relative path, without `.` or `..`"))
+		}
+		if !safe_path::is_resource_namespace(self.namespace()) {
+			return Err(invalid(self.namespace(), "namespace", "a valid resource location namespace"))
+		}
+		if let Some(path) = self.path() {
+			if !safe_path::is_resource_path(path) {
+				return Err(invalid(path, "asset path", "a valid resource location path"))
+			}
+		}
+
+		Ok(())
+	}
+
+	#[inline]
+	fn dir(&self) -> &str {
+		self.dir.as_deref().unwrap_or(DEFAULT_DIR).trim_end_matches('/')
+	}
+
+	#[inline]
+	fn namespace(&self) -> &str {
+		self.namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE)
+	}
+
+	/// `None` for the root of the namespace
+	#[inline]
+	fn path(&self) -> Option<&str> {
+		self.path.as_deref()
+			.map(|p| p.trim_matches('/'))
+			.filter(|p| !p.is_empty())
+	}
+
+	fn prefix(&self) -> String {
+		let namespace = self.namespace();
+		match self.path() {
+			Some(path) => { format!("assets/{namespace}/{path}") }
+			None => { format!("assets/{namespace}") }
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_util::{ self, mc_version };
+	use crate::util::ron2 as ron;
+
+	fn copy_files(s: &str) -> CopyFiles {
+		match ron::from_str(s).unwrap() {
+			super::super::Generator::CopyFiles { gen } => { gen }
+			_ => { unreachable!() }
+		}
+	}
+
+	fn provider_dir() -> String {
+		format!("{}/textures/dirt/handmade/all", test_util::fixture("basic"))
+	}
+
+	fn ctx() -> LoadContext {
+		LoadContext::new(crate::load::Strictness::Strict)
+	}
+
+	#[tokio::test]
+	async fn missing_dir_fails_on_load() {
+		let mut gen = copy_files(r#"(type: "copy-files")"#);
+		gen.load(&provider_dir(), &mut ctx()).await.unwrap();
+		assert_eq!(vec!["dirt.png", "extra/dirt_side.png"], gen.files);
+
+		let mut gen = copy_files(r#"(type: "copy-files", dir: "nonexistent")"#);
+		gen.load(&provider_dir(), &mut ctx()).await.unwrap_err();
+	}
+
+	#[tokio::test]
+	async fn paths_cant_escape() {
+		for manifest in [
+			r#"(type: "copy-files", dir: "/etc")"#,
+			r#"(type: "copy-files", dir: "../../stone")"#,
+			r#"(type: "copy-files", namespace: "..")"#,
+			r#"(type: "copy-files", path: "../../..")"#,
+			r#"(type: "copy-files", path: "textures/./block")"#
+		] {
+			let mut gen = copy_files(manifest);
+			let error = gen.load(&provider_dir(), &mut ctx()).await.unwrap_err();
+			assert!(matches!(error, Error::InvalidPath { .. }), "{manifest}");
+		}
+	}
+
+	#[tokio::test]
+	async fn namespace_and_path() {
+		let mut gen = copy_files(r#"(type: "copy-files", namespace: "uwu", path: "/textures/item/")"#);
+		gen.load(&provider_dir(), &mut ctx()).await.unwrap();
+
+		let mut files = Files::new();
+		gen.generate(mc_version("1.20.4"), &mut files).await.unwrap();
+		assert!(files.get("assets/uwu/textures/item/dirt.png").is_some());
+		assert!(files.get("assets/uwu/textures/item/extra/dirt_side.png").is_some());
+		assert_eq!(
+			("files/dirt.png".to_string(), "assets/uwu/textures/item/dirt.png".to_string()),
+			gen.copies()[0]
+		);
+	}
+
+	#[tokio::test]
+	async fn follows_symlinks_once() {
+		let scratch = test_util::scratch_dir("copy-files-symlinks");
+		let files = scratch.join("files");
+		::std::fs::create_dir_all(files.join("real")).unwrap();
+		::std::fs::write(files.join("real/stone.png"), b"png").unwrap();
+		::std::os::unix::fs::symlink(files.join("real"), files.join("linked")).unwrap();
+		::std::os::unix::fs::symlink(&files, files.join("real/loop")).unwrap();
+
+		let mut ctx = LoadContext::new(crate::load::Strictness::Lenient);
+		let mut gen = copy_files(r#"(type: "copy-files")"#);
+		gen.load(scratch.to_str().unwrap(), &mut ctx).await.unwrap();
+		assert_eq!(1, gen.files.len());
+		assert!(gen.files[0].ends_with("/stone.png"), "{:?}", gen.files);
+
+		// whichever of `real` and `linked` is read second, and the loop
+		let diagnostics = ctx.into_diagnostics();
+		assert_eq!(2, diagnostics.len(), "{diagnostics:?}");
+
+		let mut files = Files::new();
+		gen.generate(mc_version("1.20.4"), &mut files).await.unwrap();
+		assert_eq!(1, files.len());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[test]
+	fn needs_pack_format() {
+		let gen = copy_files(r#"(type: "copy-files")"#);
+		assert!(gen.is_available_for(mc_version("1.20.4")));
+		assert!(!gen.is_available_for(mc_version("1.5.2")));
+	}
+}
@@ -1,14 +1,24 @@
 #![deprecated]
 
+mod block;
+mod copy_files;
 mod random_cube_all;
 mod random_leaves;
 
+pub use self::block::{ BlockID, RandomVariants, Rotation, VariantSpec, Weight };
+pub use self::copy_files::CopyFiles;
+pub(crate) use self::copy_files::DEFAULT_DIR as COPY_FILES_DEFAULT_DIR;
 pub use self::random_cube_all::RandomCubeAll;
 pub use self::random_leaves::RandomLeaves;
+use crate::build::Files;
+use crate::error::build_err;
+use crate::load::LoadContext;
+use crate::util::error::Result;
 use ::mc_versions::MCVersionRef;
+use ::schemars::JsonSchema;
 use ::serde::{ Deserialize, Serialize };
 
-#[derive(Deserialize, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(tag = "type")]
 pub enum Generator {
 	#[serde(rename = "random-cube-all")]
@@ -20,22 +30,54 @@ pub enum Generator {
 	RandomLeaves {
 		#[serde(flatten)]
 		gen: RandomLeaves
+	},
+	#[serde(rename = "copy-files")]
+	CopyFiles {
+		#[serde(flatten)]
+		gen: CopyFiles
 	}
 }
 
 impl Generator {
+	/// Reads anything the generator needs from the provider dir, so that
+	/// generating can't fail halfway through a build because of a missing file
+	pub(crate) async fn load(&mut self, provider_dir: &str, ctx: &mut LoadContext) -> Result<()> {
+		use Generator::*;
+		match self {
+			RandomCubeAll { .. } => { Ok(()) }
+			RandomLeaves { .. } => { Ok(()) }
+			CopyFiles { gen } => { gen.load(provider_dir, ctx).await }
+		}
+	}
+
 	pub fn is_available_for(&self, mc_version: MCVersionRef) -> bool {
 		use Generator::*;
 		match self {
 			RandomCubeAll { gen } => { gen.is_available_for(mc_version) }
 			RandomLeaves { gen } => { gen.is_available_for(mc_version) }
+			CopyFiles { gen } => { gen.is_available_for(mc_version) }
+		}
+	}
+
+	/// Files from the provider dir that end up in the pack, as their path
+	/// relative to the provider dir, and their path in the pack
+	pub(crate) fn copies(&self) -> Vec<(String, String)> {
+		use Generator::*;
+		match self {
+			RandomCubeAll { .. } => { Vec::new() }
+			RandomLeaves { .. } => { Vec::new() }
+			CopyFiles { gen } => { gen.copies() }
 		}
 	}
-}
 
-// #[derive(Deserialize, Serialize)]
-// pub(super) enum PackVersionSpecMeta {
-// 	PackVersion(u8),
-// 	MCVersion(String),
-// 	MCVersionRange(String, String)
-// }
+	pub async fn generate(&self, mc_version: MCVersionRef, files: &mut Files)
+		-> Result<(), build_err::AddFile>
+	{
+		use Generator::*;
+		match self {
+			RandomCubeAll { gen } => { gen.generate(mc_version, files) }
+			RandomLeaves { gen } => { gen.generate(mc_version, files) }
+			CopyFiles { gen } => { gen.generate(mc_version, files).await }
+		}
+	}
+}
@@ -1,16 +1,146 @@
+use crate::build::Files;
+use crate::error::build_err;
+use super::block::{ self, BlockID, BlockPaths, Blockstate, Model, RandomVariants };
 use ::mc_versions::MCVersionRef;
+use ::schemars::JsonSchema;
 use ::serde::{ Deserialize, Serialize };
 
-#[derive(Deserialize, Serialize)]
+/// Randomly rotated (and optionally mirrored) block, that uses the same
+/// texture on all sides, like vanilla stone
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 pub struct RandomCubeAll {
-	block_id: String,
-	y: Option<Vec<Option<u16>>>,
+	block_id: BlockID,
+	#[serde(flatten)]
+	variants: RandomVariants,
+	/// also use a mirrored model for each variant (with the same rotation and
+	/// weight as the unmirrored one). Defaults to `false`
+	#[serde(skip_serializing_if = "Option::is_none")]
 	mirror: Option<bool>
 }
 
 impl RandomCubeAll {
 	#[inline]
 	pub fn is_available_for(&self, mc_version: MCVersionRef) -> bool {
-		false
+		block::is_available_for(mc_version)
+	}
+
+	pub fn generate(&self, mc_version: MCVersionRef, files: &mut Files)
+		-> Result<(), build_err::AddFile>
+	{
+		let paths = BlockPaths::new(&self.block_id, mc_version);
+		let id = paths.id();
+		let mirrored_id = format!("{id}_mirrored");
+
+		let specs = self.variants.specs();
+		let mirror = self.mirror.unwrap_or(false);
+
+		let mut models = vec![id];
+		if mirror { models.push(&mirrored_id) }
+
+		let variants = models.iter()
+			.flat_map(|model| specs.iter().map(|spec| spec.to_variant(paths.model_ref(model))))
+			.collect();
+		let blockstate = Blockstate::single(paths.no_properties_variant(), variants);
+		files.add_json(paths.blockstate_path(), &blockstate)?;
+
+		let textures = [("all", paths.texture_ref(id))];
+
+		let model = Model::new(paths.builtin_parent_ref("cube_all"), &textures);
+		files.add_json(paths.model_path(id), &model)?;
+
+		if mirror {
+			let model = Model::new(paths.builtin_parent_ref("cube_mirrored_all"), &textures);
+			files.add_json(paths.model_path(&mirrored_id), &model)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_util::{ generate, json, parse_gen };
+
+	#[test]
+	fn flattened() {
+		let files = generate("random-cube-all", r#"(block_id: "stone", y: [None, 180], mirror: true)"#, "1.20.4");
+		assert_eq!(3, files.len());
+
+		assert_eq!(json(&files, "assets/minecraft/blockstates/stone.json"), ::serde_json::json!({
+			"variants": {
+				"": [
+					{ "model": "minecraft:block/stone" },
+					{ "model": "minecraft:block/stone", "y": 180 },
+					{ "model": "minecraft:block/stone_mirrored" },
+					{ "model": "minecraft:block/stone_mirrored", "y": 180 }
+				]
+			}
+		}));
+
+		assert_eq!(json(&files, "assets/minecraft/models/block/stone.json"), ::serde_json::json!({
+			"parent": "minecraft:block/cube_all",
+			"textures": { "all": "minecraft:block/stone" }
+		}));
+
+		assert_eq!(json(&files, "assets/minecraft/models/block/stone_mirrored.json"), ::serde_json::json!({
+			"parent": "minecraft:block/cube_mirrored_all",
+			"textures": { "all": "minecraft:block/stone" }
+		}));
+	}
+
+	#[test]
+	fn legacy() {
+		let files = generate("random-cube-all", r#"(block_id: "stone")"#, "1.12.2");
+		assert_eq!(2, files.len());
+
+		assert_eq!(json(&files, "assets/minecraft/blockstates/stone.json"), ::serde_json::json!({
+			"variants": {
+				"normal": [
+					{ "model": "minecraft:stone" },
+					{ "model": "minecraft:stone", "y": 90 },
+					{ "model": "minecraft:stone", "y": 180 },
+					{ "model": "minecraft:stone", "y": 270 }
+				]
+			}
+		}));
+
+		assert_eq!(json(&files, "assets/minecraft/models/block/stone.json"), ::serde_json::json!({
+			"parent": "block/cube_all",
+			"textures": { "all": "minecraft:blocks/stone" }
+		}));
+	}
+
+	#[test]
+	fn weighted_variants() {
+		let files = generate("random-cube-all", r#"(
+			block_id: "stone",
+			variants: [
+				(weight: 9),
+				(x: 90, y: 270, uvlock: true)
+			],
+			mirror: true
+		)"#, "1.20.4");
+
+		assert_eq!(json(&files, "assets/minecraft/blockstates/stone.json"), ::serde_json::json!({
+			"variants": {
+				"": [
+					{ "model": "minecraft:block/stone", "weight": 9 },
+					{ "model": "minecraft:block/stone", "x": 90, "y": 270, "uvlock": true },
+					{ "model": "minecraft:block/stone_mirrored", "weight": 9 },
+					{ "model": "minecraft:block/stone_mirrored", "x": 90, "y": 270, "uvlock": true }
+				]
+			}
+		}));
+	}
+
+	#[test]
+	fn invalid_meta() {
+		parse_gen("random-cube-all", r#"(block_id: "stone", y: [45])"#).unwrap_err();
+		parse_gen("random-cube-all", r#"(block_id: "Stone")"#).unwrap_err();
+		parse_gen("random-cube-all", r#"(block_id: "stone", y: [])"#).unwrap_err();
+		parse_gen("random-cube-all", r#"(block_id: "stone", variants: [(weight: 0)])"#).unwrap_err();
+		parse_gen("random-cube-all", r#"(block_id: "stone", variants: [(x: 45)])"#).unwrap_err();
+		parse_gen("random-cube-all", r#"(block_id: "stone", y: [None], variants: [()])"#).unwrap_err();
 	}
 }
@@ -1,15 +1,94 @@
+use crate::build::Files;
+use crate::error::build_err;
+use super::block::{ self, BlockID, BlockPaths, Blockstate, Model, RandomVariants };
 use ::mc_versions::MCVersionRef;
+use ::schemars::JsonSchema;
 use ::serde::{ Deserialize, Serialize };
 
-#[derive(Deserialize, Serialize)]
+/// Randomly rotated leaves. Uses the builtin `leaves` model as parent, so
+/// the block keeps its tint (biome colour).
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 pub struct RandomLeaves {
-	block_id: String,
-	y: Option<Vec<Option<u16>>>
+	block_id: BlockID,
+	#[serde(flatten)]
+	variants: RandomVariants
 }
 
 impl RandomLeaves {
 	#[inline]
 	pub fn is_available_for(&self, mc_version: MCVersionRef) -> bool {
-		false
+		block::is_available_for(mc_version)
+	}
+
+	pub fn generate(&self, mc_version: MCVersionRef, files: &mut Files)
+		-> Result<(), build_err::AddFile>
+	{
+		let paths = BlockPaths::new(&self.block_id, mc_version);
+		let id = paths.id();
+
+		let specs = self.variants.specs();
+
+		// leaves have block state properties (distance, persistent, ...), but
+		// the empty variant (or `normal` before 1.13, where those properties
+		// are ignored for leaves) matches all of them
+		let variants = specs.iter()
+			.map(|spec| spec.to_variant(paths.model_ref(id)))
+			.collect();
+		let blockstate = Blockstate::single(paths.no_properties_variant(), variants);
+		files.add_json(paths.blockstate_path(), &blockstate)?;
+
+		let textures = [("all", paths.texture_ref(id))];
+		let model = Model::new(paths.builtin_parent_ref("leaves"), &textures);
+		files.add_json(paths.model_path(id), &model)?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_util::{ generate, json, parse_gen };
+
+	#[test]
+	fn flattened() {
+		let files = generate("random-leaves", r#"(block_id: "oak_leaves", y: [None, 90])"#, "1.20.4");
+		assert_eq!(2, files.len());
+
+		assert_eq!(json(&files, "assets/minecraft/blockstates/oak_leaves.json"), ::serde_json::json!({
+			"variants": {
+				"": [
+					{ "model": "minecraft:block/oak_leaves" },
+					{ "model": "minecraft:block/oak_leaves", "y": 90 }
+				]
+			}
+		}));
+
+		assert_eq!(json(&files, "assets/minecraft/models/block/oak_leaves.json"), ::serde_json::json!({
+			"parent": "minecraft:block/leaves",
+			"textures": { "all": "minecraft:block/oak_leaves" }
+		}));
+	}
+
+	#[test]
+	fn legacy() {
+		let files = generate("random-leaves", r#"(block_id: "oak_leaves")"#, "1.12.2");
+		assert_eq!(2, files.len());
+
+		assert_eq!(json(&files, "assets/minecraft/blockstates/oak_leaves.json"), ::serde_json::json!({
+			"variants": {
+				"normal": [
+					{ "model": "minecraft:oak_leaves" },
+					{ "model": "minecraft:oak_leaves", "y": 90 },
+					{ "model": "minecraft:oak_leaves", "y": 180 },
+					{ "model": "minecraft:oak_leaves", "y": 270 }
+				]
+			}
+		}));
+
+		assert_eq!(json(&files, "assets/minecraft/models/block/oak_leaves.json"), ::serde_json::json!({
+			"parent": "block/leaves",
+			"textures": { "all": "minecraft:blocks/oak_leaves" }
+		}));
 	}
 }
@@ -0,0 +1,724 @@
+//! Importing existing (vanilla format) resource packs as pack sources
+
+use crate::{ option2, pack2, provider2, texture2 };
+use crate::error::common::ManifestError;
+use crate::error::import_err::{ self, ImportPack };
+use crate::gen::{ CopyFiles, Generator, COPY_FILES_DEFAULT_DIR };
+use crate::provider2::PackVersionSpecMeta;
+use crate::scaffold;
+use crate::util::create_path_builder3;
+use crate::util::fs2;
+use crate::util::safe_path;
+use ::mc_versions::MC_VERSIONS;
+use ::serde::Deserialize;
+use ::std::collections::BTreeMap;
+use ::std::io::{ Cursor, Read as _ };
+use ::wiwipaccer_common::mc_id::DEFAULT_NAMESPACE;
+
+const MCMETA: &str = "pack.mcmeta";
+const OPTION_ID: &str = "imported";
+const OPTION_NAME: &str = "Imported";
+const PROVIDER_ID: &str = "all";
+/// Most the files in a zip can add up to once decompressed, since they're all
+/// read into memory (the sizes in the zip can't be trusted for this)
+const MAX_UNZIPPED_SIZE: u64 = 1024 * 1024 * 1024;
+/// Added to `dest` for the dir the pack is written to before it's moved there
+const TEMP_DIR_SUFFIX: &str = ".import-tmp";
+
+/// What was imported
+#[derive(Debug)]
+pub struct Import {
+	textures: Vec<String>,
+	skipped: Vec<Skipped>
+}
+
+/// A file in the resource pack that wasn't imported
+#[derive(Debug, PartialEq, Eq)]
+pub struct Skipped {
+	path: String,
+	reason: SkipReason
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+	/// not an asset (ex. `pack.png`, or data pack files)
+	NotAnAsset,
+	/// in a namespace that isn't a valid resource location namespace (ex. one
+	/// with uppercase letters), which the provider couldn't copy into
+	InvalidNamespace,
+	/// in an asset dir that isn't a valid resource location path, which the
+	/// provider couldn't copy into
+	InvalidPath
+}
+
+impl Import {
+	/// IDs of the textures that were created
+	#[inline]
+	pub fn textures(&self) -> &[String] {
+		&self.textures
+	}
+
+	/// Files in the resource pack that weren't imported, and why
+	#[inline]
+	pub fn skipped(&self) -> &[Skipped] {
+		&self.skipped
+	}
+}
+
+impl Skipped {
+	#[inline]
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	#[inline]
+	pub fn reason(&self) -> SkipReason {
+		self.reason
+	}
+}
+
+impl ::std::fmt::Display for SkipReason {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+		match self {
+			Self::NotAnAsset => { f.write_str("not an asset") }
+			Self::InvalidNamespace => { f.write_str("namespace isn't a valid resource location namespace") }
+			Self::InvalidPath => { f.write_str("asset dir isn't a valid resource location path") }
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct Mcmeta {
+	pack: McmetaPack
+}
+
+#[derive(Deserialize)]
+struct McmetaPack {
+	pack_format: u8,
+	#[serde(default)]
+	description: ::serde_json::Value
+}
+
+/// Files of one texture: an asset dir (ex. `assets/minecraft/textures/block`)
+#[derive(Default)]
+struct Group {
+	namespace: String,
+	/// empty for files at the root of the namespace (ex. `sounds.json`)
+	path: String,
+	/// relative to the asset dir
+	files: Vec<(String, Vec<u8>)>
+}
+
+/// Imports the resource pack at `source` (a zip file or a directory) as a new
+/// pack source in `dest`, which can't have a pack (or a textures dir) in it
+/// already.
+///
+/// Each asset dir (ex. `assets/minecraft/textures/block`, or
+/// `assets/minecraft/blockstates`) becomes a texture, with one option holding
+/// the files in that dir, in a provider that copies them. The provider is
+/// available for the minecraft versions using the resource pack's
+/// `pack_format`.
+///
+/// The pack is written to a dir next to `dest` first, and only moved into
+/// place once all of it is written, so a failed import doesn't leave half a
+/// pack behind.
+pub async fn import_resource_pack(source: &str, dest: &str, id: &str, name: &str)
+	-> Result<Import, ImportPack>
+{
+	scaffold::check_id("pack", id)?;
+	let p = create_path_builder3().with_root_dir(dest);
+	scaffold::refuse_existing(&p.root_manifest_unchecked()).await?;
+	scaffold::refuse_existing(&p.texture_entries_dir_unchecked()).await?;
+
+	let files = match fs2::is_dir(|| source.into()).await? {
+		true => { read_dir_files(source).await? }
+		false => { read_zip_files(source, MAX_UNZIPPED_SIZE).await? }
+	};
+	let files = strip_root_dir(files);
+
+	let mcmeta = files.iter()
+		.find(|(path, _)| path == MCMETA)
+		.ok_or_else(|| import_err::no_mcmeta(source.into()))?;
+	let mcmeta = ::serde_json::from_slice::<Mcmeta>(&mcmeta.1).map_err(import_err::mcmeta)?;
+	let versions = versions_for(mcmeta.pack.pack_format)?;
+	let description = text(&mcmeta.pack.description);
+	let description = Some(&*description).filter(|d| !d.is_empty());
+
+	let (groups, skipped) = group(files);
+
+	let temp_dir = format!("{}{TEMP_DIR_SUFFIX}", dest.trim_end_matches('/'));
+	scaffold::refuse_existing(&temp_dir).await?;
+
+	let pack = Pack { id, name, description, versions, groups };
+	let textures = match write_pack(&temp_dir, pack).await {
+		Ok(textures) => { textures }
+		Err(e) => {
+			// the error that matters is the one writing, not cleaning up
+			let _ = fs2::remove_dir_all(|| temp_dir.clone()).await;
+			return Err(e)
+		}
+	};
+	move_into_place(&temp_dir, dest).await?;
+
+	Ok(Import { textures, skipped })
+}
+
+/// Turns an ID-ish string (ex. a file name) into a valid ID, replacing
+/// anything that isn't allowed with underscores
+pub fn sanitise_id(s: &str) -> String {
+	s.chars()
+		.map(|c| c.to_ascii_lowercase())
+		.map(|c| match c {
+			'a'..='z' | '0'..='9' | '_' => { c }
+			_ => { '_' }
+		})
+		.collect()
+}
+
+/// Everything needed to write an imported pack
+struct Pack<'h> {
+	id: &'h str,
+	name: &'h str,
+	description: Option<&'h str>,
+	versions: Vec<PackVersionSpecMeta>,
+	groups: BTreeMap<String, Group>
+}
+
+/// Writes `pack` as a pack source in `dir`, returning the IDs of the textures
+/// that were created
+async fn write_pack(dir: &str, pack: Pack<'_>) -> Result<Vec<String>, ImportPack> {
+	let p = create_path_builder3().with_root_dir(dir);
+	let manifest = pack2::new_pack(pack.id, pack.name, pack.description);
+	write_manifest(p.texture_entries_dir_unchecked(), p.root_manifest_unchecked(), manifest).await?;
+
+	let mut textures = Vec::with_capacity(pack.groups.len());
+	for (texture_id, group) in pack.groups {
+		let p = p.clone().with_texture_id(&texture_id);
+		let texture_name = match &*group.path {
+			"" => { group.namespace.clone() }
+			path => { format!("{}/{path}", group.namespace) }
+		};
+		write_manifest(p.texture_dir_unchecked(), p.texture_manifest_unchecked(), texture2::new_texture(&texture_name, Some(OPTION_ID))).await?;
+
+		let p = p.with_option_id(OPTION_ID);
+		write_manifest(p.option_dir_unchecked(), p.option_manifest_unchecked(), option2::new_option(OPTION_NAME)).await?;
+
+		let p = p.with_provider_id(PROVIDER_ID);
+		let namespace = Some(group.namespace).filter(|n| n != DEFAULT_NAMESPACE);
+		let path = Some(group.path).filter(|p| !p.is_empty());
+		let gen = Generator::CopyFiles { gen: CopyFiles::with_target(namespace, path) };
+		let manifest = provider2::new_provider(Some(pack.versions.clone()), gen);
+		let provider_dir = p.provider_dir_unchecked();
+		write_manifest(provider_dir.clone(), p.provider_manifest_unchecked(), manifest).await?;
+
+		for (path, contents) in group.files {
+			let path = format!("{provider_dir}/{COPY_FILES_DEFAULT_DIR}/{path}");
+			if let Some((parent, _)) = path.rsplit_once('/') {
+				fs2::create_dir_all(|| parent.into()).await?;
+			}
+			fs2::write(|| path.clone(), contents).await?;
+		}
+
+		textures.push(texture_id);
+	}
+
+	Ok(textures)
+}
+
+/// Moves the pack written to `temp_dir` to `dest`. If `dest` doesn't exist
+/// that's one rename, otherwise its textures dir and then its manifest are
+/// moved in, so `dest` only becomes a pack once everything is there.
+async fn move_into_place(temp_dir: &str, dest: &str) -> Result<(), ImportPack> {
+	if !fs2::exists(|| dest.into()).await? {
+		return match fs2::rename(|| temp_dir.into(), || dest.into()).await {
+			Ok(()) => { Ok(()) }
+			Err(e) => {
+				let _ = fs2::remove_dir_all(|| temp_dir.into()).await;
+				Err(e.into())
+			}
+		}
+	}
+
+	let from = create_path_builder3().with_root_dir(temp_dir);
+	let to = create_path_builder3().with_root_dir(dest);
+
+	let textures = fs2::rename(
+		|| from.texture_entries_dir_unchecked(),
+		|| to.texture_entries_dir_unchecked()
+	).await;
+	if let Err(e) = textures {
+		let _ = fs2::remove_dir_all(|| temp_dir.into()).await;
+		return Err(e.into())
+	}
+
+	let manifest = fs2::rename(
+		|| from.root_manifest_unchecked(),
+		|| to.root_manifest_unchecked()
+	).await;
+	if let Err(e) = manifest {
+		let _ = fs2::remove_dir_all(|| to.texture_entries_dir_unchecked()).await;
+		let _ = fs2::remove_dir_all(|| temp_dir.into()).await;
+		return Err(e.into())
+	}
+
+	fs2::remove_dir_all(|| temp_dir.into()).await?;
+	Ok(())
+}
+
+async fn write_manifest<E: Into<ManifestError>>(
+	dir: String,
+	path: String,
+	manifest: Result<String, E>
+) -> Result<(), ImportPack> {
+	let manifest = scaffold::manifest_contents(&path, manifest)?;
+	fs2::create_dir_all(|| dir.clone()).await?;
+	fs2::write(|| path.clone(), manifest).await?;
+	Ok(())
+}
+
+/// All files in `dir`, with paths relative to it. Symlinks are refused, since
+/// they could point outside of it (or back into it, over and over)
+async fn read_dir_files(dir: &str) -> Result<Vec<(String, Vec<u8>)>, ImportPack> {
+	// dirs still to read, relative to dir ("" being itself)
+	let mut dirs = vec![String::new()];
+	let mut files = Vec::new();
+
+	while let Some(relative_dir) = dirs.pop() {
+		let full_dir = match &*relative_dir {
+			"" => { dir.to_string() }
+			relative_dir => { format!("{dir}/{relative_dir}") }
+		};
+
+		let mut read_dir = fs2::read_dir(|| full_dir.clone()).await?;
+		while let Some(entry) = read_dir.next().await? {
+			let path = entry.path();
+			let name = entry.file_name()
+				.into_string()
+				.map_err(|_| import_err::non_utf8_path(path.to_string_lossy().into_owned()))?;
+			let relative = match &*relative_dir {
+				"" => { name }
+				relative_dir => { format!("{relative_dir}/{name}") }
+			};
+			check_path(&relative)?;
+
+			let full_path = format!("{dir}/{relative}");
+			let metadata = fs2::symlink_metadata(|| full_path.clone()).await?;
+			if metadata.is_symlink() {
+				return Err(import_err::symlink(relative))
+			}
+			if metadata.is_dir() {
+				dirs.push(relative);
+			} else {
+				let contents = fs2::read(|| full_path.clone())
+					.await
+					.map_err(|e| import_err::read(full_path.clone(), e))?;
+				files.push((relative, contents));
+			}
+		}
+	}
+
+	Ok(files)
+}
+
+/// All files in the zip file at `path`, failing if they add up to more than
+/// `limit` bytes
+async fn read_zip_files(path: &str, limit: u64) -> Result<Vec<(String, Vec<u8>)>, ImportPack> {
+	let bytes = fs2::read(|| path.into())
+		.await
+		.map_err(|e| import_err::read(path.into(), e))?;
+	let zip_err = |e| import_err::zip(path.into(), e);
+
+	let mut archive = ::zip::ZipArchive::new(Cursor::new(bytes)).map_err(zip_err)?;
+	let mut files = Vec::with_capacity(archive.len());
+	let mut remaining = limit;
+
+	for i in 0..archive.len() {
+		let mut file = archive.by_index(i).map_err(zip_err)?;
+		if file.is_dir() { continue }
+
+		// `enclosed_name` is `None` for absolute paths, or ones with `..` in them
+		let name = match file.enclosed_name() {
+			Some(name) => {
+				name.to_str()
+					.ok_or_else(|| import_err::non_utf8_path(name.to_string_lossy().into_owned()))?
+					.to_string()
+			}
+			None => { return Err(import_err::unsafe_path(file.name().into())) }
+		};
+		check_path(&name)?;
+
+		// one more byte than is left, to tell if it goes over
+		let mut contents = Vec::new();
+		(&mut file).take(remaining + 1)
+			.read_to_end(&mut contents)
+			.map_err(|e| zip_err(e.into()))?;
+		remaining = remaining.checked_sub(contents.len() as u64)
+			.ok_or_else(|| import_err::zip_too_large(path.into(), limit))?;
+		files.push((name, contents));
+	}
+
+	Ok(files)
+}
+
+/// Paths in a resource pack end up joined onto the pack's dir, so they need to
+/// stay inside it (ex. not `../x`, or `assets/./x`)
+fn check_path(path: &str) -> Result<(), ImportPack> {
+	match safe_path::is_normalised_relative(path) {
+		true => { Ok(()) }
+		false => { Err(import_err::unsafe_path(path.into())) }
+	}
+}
+
+/// Zipping a resource pack's dir (instead of what's in it) puts everything
+/// in a dir, which minecraft won't load, but is easy enough to handle here
+fn strip_root_dir(files: Vec<(String, Vec<u8>)>) -> Vec<(String, Vec<u8>)> {
+	if files.iter().any(|(path, _)| path == MCMETA) { return files }
+
+	let root = match files.first().and_then(|(path, _)| path.split_once('/')) {
+		Some((root, _)) => { format!("{root}/") }
+		None => { return files }
+	};
+	if !files.iter().all(|(path, _)| path.starts_with(&root)) { return files }
+
+	files.into_iter()
+		.map(|(path, contents)| (path[root.len()..].into(), contents))
+		.collect()
+}
+
+/// Minecraft versions using `pack_format`. Versions using a format are
+/// usually next to each other, but not always (ex. a snapshot in between using
+/// a different one), so this is a range for each run of them. Versions with an
+/// unknown format (lots of snapshots) don't end a run.
+fn versions_for(pack_format: u8) -> Result<Vec<PackVersionSpecMeta>, ImportPack> {
+	let mut runs = Vec::<Vec<&str>>::new();
+	let mut in_run = false;
+
+	// newest first
+	for version in MC_VERSIONS.iter() {
+		match version.pack_format.get_version() {
+			Some(v) if v == pack_format => {}
+			Some(_) => {
+				in_run = false;
+				continue
+			}
+			None => { continue }
+		}
+
+		match runs.last_mut() {
+			Some(run) if in_run => { run.push(version.name) }
+			_ => { runs.push(vec![version.name]) }
+		}
+		in_run = true;
+	}
+
+	if runs.is_empty() { return Err(import_err::unknown_pack_format(pack_format)) }
+
+	// oldest first, like manifests are usually written
+	Ok(runs.into_iter().rev().map(|run| match &*run {
+		[version] => { PackVersionSpecMeta::MCVersion(version.to_string()) }
+		[newest, .., oldest] => {
+			PackVersionSpecMeta::MCVersionRange(oldest.to_string(), newest.to_string())
+		}
+		[] => { unreachable!("runs are never empty") }
+	}).collect())
+}
+
+/// Plain text of a text component (a `description`), without formatting
+fn text(component: &::serde_json::Value) -> String {
+	use ::serde_json::Value::*;
+	match component {
+		String(s) => { s.clone() }
+		Array(components) => { components.iter().map(text).collect() }
+		Object(o) => {
+			let mut s = o.get("text").map(text).unwrap_or_default();
+			if let Some(extra) = o.get("extra") { s.push_str(&text(extra)) }
+			s
+		}
+		_ => { ::std::string::String::new() }
+	}
+}
+
+/// Groups asset files by asset dir (or namespace, for files at the root of
+/// one), keyed by the ID of the texture they'll be
+/// in, returning those and the files that can't be imported. Asset dirs the
+/// provider couldn't copy into (since the namespace or path isn't valid) are
+/// skipped, rather than importing a pack that fails to load.
+fn group(files: Vec<(String, Vec<u8>)>) -> (BTreeMap<String, Group>, Vec<Skipped>) {
+	let mut groups = BTreeMap::<(String, String), Group>::new();
+	let mut skipped = Vec::new();
+
+	for (path, contents) in files {
+		if path == MCMETA { continue }
+
+		let parts = path.split('/').collect::<Vec<_>>();
+		let grouped = match &*parts {
+			// ex. assets/minecraft/textures/block/stone.png
+			["assets", namespace, kind, dir, rest @ ..] if !rest.is_empty() => {
+				Ok((namespace.to_string(), format!("{kind}/{dir}"), rest.join("/")))
+			}
+			// ex. assets/minecraft/blockstates/stone.json
+			["assets", namespace, kind, file] => {
+				Ok((namespace.to_string(), kind.to_string(), file.to_string()))
+			}
+			// ex. assets/minecraft/sounds.json
+			["assets", namespace, file] => {
+				Ok((namespace.to_string(), String::new(), file.to_string()))
+			}
+			_ => { Err(SkipReason::NotAnAsset) }
+		};
+		let grouped = grouped.and_then(|(namespace, asset_dir, file)| {
+			if !safe_path::is_resource_namespace(&namespace) { return Err(SkipReason::InvalidNamespace) }
+			if !asset_dir.is_empty() && !safe_path::is_resource_path(&asset_dir) { return Err(SkipReason::InvalidPath) }
+			Ok((namespace, asset_dir, file))
+		});
+
+		let (namespace, asset_dir, file) = match grouped {
+			Ok(grouped) => { grouped }
+			Err(reason) => {
+				skipped.push(Skipped { path, reason });
+				continue
+			}
+		};
+
+		let group = groups.entry((namespace.clone(), asset_dir.clone())).or_default();
+		group.namespace = namespace;
+		group.path = asset_dir;
+		group.files.push((file, contents));
+	}
+
+	// different asset dirs could end up with the same ID
+	let mut by_id = BTreeMap::new();
+	for ((namespace, asset_dir), mut group) in groups {
+		let base = match &*asset_dir {
+			"" => { sanitise_id(&namespace) }
+			asset_dir => { sanitise_id(&format!("{namespace}_{asset_dir}")) }
+		};
+		let mut id = base.clone();
+		let mut n = 1;
+		while by_id.contains_key(&id) {
+			n += 1;
+			id = format!("{base}_{n}");
+		}
+
+		group.files.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+		by_id.insert(id, group);
+	}
+
+	skipped.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+	(by_id, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::build;
+	use crate::error::build_err;
+	use crate::load::Strictness;
+	use crate::test_util;
+	use crate::validate;
+	use crate::workspace2::{ self, WorkspaceRuntime };
+	use ::std::io::Write as _;
+
+	fn write_zip(path: &::std::path::Path, files: &[(&str, &str)]) {
+		let mut zip = ::zip::ZipWriter::new(::std::fs::File::create(path).unwrap());
+		for (name, contents) in files {
+			zip.start_file(*name, ::zip::write::FileOptions::default()).unwrap();
+			zip.write_all(contents.as_bytes()).unwrap();
+		}
+		zip.finish().unwrap();
+	}
+
+	#[test]
+	fn versions_from_pack_format() {
+		let versions = versions_for(22).unwrap();
+		assert!(matches!(
+			&*versions,
+			[PackVersionSpecMeta::MCVersionRange(_, newest)] if newest == "1.20.4"
+		));
+		assert!(versions_for(255).is_err());
+	}
+
+	#[test]
+	fn text_components() {
+		let component = ::serde_json::json!([{ "text": "uwu", "color": "red", "extra": [" owo"] }, "!"]);
+		assert_eq!("uwu owo!", text(&component));
+	}
+
+	#[tokio::test]
+	async fn imported_zip_builds() {
+		let scratch = test_util::scratch_dir("import");
+
+		// zipped the dir, instead of what's in it
+		let zip = scratch.join("My Pack.zip");
+		write_zip(&zip, &[
+			("My Pack/pack.mcmeta", r#"{ "pack": { "pack_format": 22, "description": "uwu" } }"#),
+			("My Pack/pack.png", "png"),
+			("My Pack/assets/minecraft/textures/block/stone.png", "stone"),
+			("My Pack/assets/minecraft/textures/block/ores/coal.png", "coal"),
+			("My Pack/assets/minecraft/blockstates/stone.json", "{}"),
+			("My Pack/assets/minecraft/sounds.json", "sounds"),
+			("My Pack/assets/my-mod/textures/item/thing.png", "thing")
+		]);
+
+		let dest = scratch.join("source");
+		let dest = dest.to_str().unwrap();
+		let import = import_resource_pack(zip.to_str().unwrap(), dest, "my_pack", "My Pack").await.unwrap();
+		assert_eq!(vec![
+			"minecraft",
+			"minecraft_blockstates",
+			"minecraft_textures_block",
+			"my_mod_textures_item"
+		], import.textures());
+		assert_eq!(vec![
+			Skipped { path: "pack.png".into(), reason: SkipReason::NotAnAsset }
+		], import.skipped());
+
+		let mut workspace = WorkspaceRuntime::new(workspace2::nr::Name::new("uwu".into()));
+		workspace.set_strictness(Strictness::Strict);
+		workspace.add_pack(dest).await.unwrap();
+		let pack = workspace.packs().next().unwrap();
+		assert!(validate::validate_pack(pack).await.unwrap().is_empty());
+
+		let mc_version = test_util::mc_version("1.20.4");
+		let files = build::build(&workspace, workspace.selections().ref_inner(), mc_version).await.unwrap();
+		assert_eq!(b"stone", files.get("assets/minecraft/textures/block/stone.png").unwrap());
+		assert_eq!(b"coal", files.get("assets/minecraft/textures/block/ores/coal.png").unwrap());
+		assert_eq!(b"{}", files.get("assets/minecraft/blockstates/stone.json").unwrap());
+		assert_eq!(b"sounds", files.get("assets/minecraft/sounds.json").unwrap());
+		assert_eq!(b"thing", files.get("assets/my-mod/textures/item/thing.png").unwrap());
+
+		// only available for the versions using the pack's format
+		let mc_version = test_util::mc_version("1.20.2");
+		let result = build::build(&workspace, workspace.selections().ref_inner(), mc_version).await;
+		assert!(matches!(result, Err(build_err::Build::NoProviderAvailable { .. })));
+
+		// can't import over it again
+		import_resource_pack(zip.to_str().unwrap(), dest, "my_pack", "My Pack").await.unwrap_err();
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn invalid_asset_dirs_are_skipped() {
+		let scratch = test_util::scratch_dir("import-invalid");
+		let zip = scratch.join("pack.zip");
+		write_zip(&zip, &[
+			("pack.mcmeta", r#"{ "pack": { "pack_format": 22 } }"#),
+			("assets/minecraft/textures/block/stone.png", "stone"),
+			("assets/minecraft/textures/Custom/x.png", "x"),
+			("assets/MyMod/textures/item/thing.png", "thing")
+		]);
+
+		let dest = scratch.join("source");
+		let dest = dest.to_str().unwrap();
+		let import = import_resource_pack(zip.to_str().unwrap(), dest, "my_pack", "My Pack").await.unwrap();
+		assert_eq!(vec!["minecraft_textures_block"], import.textures());
+		assert_eq!(vec![
+			Skipped { path: "assets/MyMod/textures/item/thing.png".into(), reason: SkipReason::InvalidNamespace },
+			Skipped { path: "assets/minecraft/textures/Custom/x.png".into(), reason: SkipReason::InvalidPath }
+		], import.skipped());
+
+		// what was imported loads
+		let mut workspace = WorkspaceRuntime::new(workspace2::nr::Name::new("uwu".into()));
+		workspace.set_strictness(Strictness::Strict);
+		workspace.add_pack(dest).await.unwrap();
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn refuses_escaping_paths() {
+		let scratch = test_util::scratch_dir("import-escaping");
+		let mcmeta = ("pack.mcmeta", r#"{ "pack": { "pack_format": 22 } }"#);
+		let dest = scratch.join("source");
+		let dest = dest.to_str().unwrap();
+
+		for path in [
+			"assets/minecraft/textures/../../../../../evil.png",
+			"/tmp/evil.png",
+			"assets/minecraft/textures/block/./stone.png",
+			"assets/minecraft//block/stone.png"
+		] {
+			let zip = scratch.join("evil.zip");
+			write_zip(&zip, &[mcmeta, (path, "evil")]);
+
+			let result = import_resource_pack(zip.to_str().unwrap(), dest, "evil", "Evil").await;
+			assert!(matches!(result, Err(ImportPack::UnsafePath { .. })), "{path}");
+		}
+
+		// nothing written, inside or outside of dest
+		assert!(!scratch.join("evil.png").exists());
+		assert!(!scratch.join("source").exists());
+		assert!(!scratch.join(format!("source{TEMP_DIR_SUFFIX}")).exists());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn zip_size_is_limited() {
+		let scratch = test_util::scratch_dir("import-zip-size");
+		let zip = scratch.join("big.zip");
+		let zip_path = zip.to_str().unwrap();
+		let big = "a".repeat(600);
+		write_zip(&zip, &[("a.png", &big), ("b.png", &big)]);
+
+		let files = read_zip_files(zip_path, 1200).await.unwrap();
+		assert_eq!(2, files.len());
+
+		let error = read_zip_files(zip_path, 1199).await.unwrap_err();
+		assert!(matches!(error, ImportPack::ZipTooLarge { limit: 1199, .. }));
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn refuses_symlinks() {
+		let scratch = test_util::scratch_dir("import-symlinks");
+		let source = scratch.join("pack");
+		::std::fs::create_dir_all(source.join("assets/minecraft")).unwrap();
+		::std::fs::write(source.join("pack.mcmeta"), r#"{ "pack": { "pack_format": 22 } }"#).unwrap();
+		::std::os::unix::fs::symlink("..", source.join("assets/minecraft/loop")).unwrap();
+
+		let dest = scratch.join("source");
+		let result = import_resource_pack(source.to_str().unwrap(), dest.to_str().unwrap(), "loop", "Loop").await;
+		assert!(matches!(result, Err(ImportPack::Symlink { path }) if path == "assets/minecraft/loop"));
+		assert!(!dest.exists());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn imports_dir_into_existing_dir() {
+		let scratch = test_util::scratch_dir("import-existing");
+
+		let source = scratch.join("pack");
+		::std::fs::create_dir_all(source.join("assets/minecraft/textures/block")).unwrap();
+		::std::fs::write(source.join("pack.mcmeta"), r#"{ "pack": { "pack_format": 22 } }"#).unwrap();
+		::std::fs::write(source.join("assets/minecraft/textures/block/stone.png"), "stone").unwrap();
+
+		let dest = scratch.join("source");
+		::std::fs::create_dir_all(&dest).unwrap();
+		::std::fs::write(dest.join("README.md"), "uwu").unwrap();
+
+		let import = import_resource_pack(
+			source.to_str().unwrap(),
+			dest.to_str().unwrap(),
+			"my_pack",
+			"My Pack"
+		).await.unwrap();
+		assert_eq!(vec!["minecraft_textures_block"], import.textures());
+
+		let p = create_path_builder3()
+			.with_root_dir(dest.to_str().unwrap())
+			.with_texture_id("minecraft_textures_block")
+			.with_option_id(OPTION_ID)
+			.with_provider_id(PROVIDER_ID);
+		assert!(::std::path::Path::new(&p.root_manifest_unchecked()).is_file());
+		let file = format!("{}/{COPY_FILES_DEFAULT_DIR}/stone.png", p.provider_dir_unchecked());
+		assert_eq!("stone", ::std::fs::read_to_string(file).unwrap());
+		assert_eq!("uwu", ::std::fs::read_to_string(dest.join("README.md")).unwrap());
+		assert!(!scratch.join(format!("source{TEMP_DIR_SUFFIX}")).exists());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+}
@@ -24,3 +24,15 @@ pub mod option2;
 pub mod provider2;
 pub mod provider3;
 pub mod gen;
+pub mod build;
+pub mod import;
+pub mod load;
+pub mod locale;
+pub mod migrate;
+pub mod scaffold;
+pub mod schema;
+pub mod validate;
+pub mod watch;
+
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,148 @@
+//! Settings and state shared while loading packs
+
+use crate::error::load_err::{ self, Diagnostic, LoadFailed, Severity };
+use crate::util::error as util_err;
+use crate::util::{ fs, fs2 };
+use ::futures::future;
+use ::serde::{ Deserialize, Serialize };
+use ::std::fmt::Display;
+use ::std::future::Future;
+use ::std::path::PathBuf;
+
+/// How problems that loading can recover from (ex. a texture's default option
+/// not existing) are handled. Serialised as a plain string (`"strict"` or
+/// `"lenient"`), since ron can't deserialise enum variants from inside other
+/// internally tagged (ie. versioned meta) enums.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "String", into = "&'static str")]
+pub enum Strictness {
+	/// recoverable problems are errors, and the pack fails to load if anything
+	/// in it does
+	#[default]
+	Strict,
+	/// recoverable problems are collected as warnings, and things that fail to
+	/// load are left out of the pack so the rest of it can still be used
+	Lenient
+}
+
+impl TryFrom<String> for Strictness {
+	type Error = String;
+
+	#[inline]
+	fn try_from(value: String) -> Result<Self, Self::Error> {
+		match &*value {
+			"strict" => { Ok(Self::Strict) }
+			"lenient" => { Ok(Self::Lenient) }
+			_ => { Err(format!("strictness must be \"strict\" or \"lenient\", got \"{value}\"")) }
+		}
+	}
+}
+
+impl From<Strictness> for &'static str {
+	#[inline]
+	fn from(value: Strictness) -> Self {
+		match value {
+			Strictness::Strict => { "strict" }
+			Strictness::Lenient => { "lenient" }
+		}
+	}
+}
+
+/// Collects problems found while loading a pack, so they can all be reported
+/// at once, instead of only the first one
+pub(crate) struct LoadContext {
+	strictness: Strictness,
+	diagnostics: Vec<Diagnostic>
+}
+
+impl LoadContext {
+	#[inline]
+	pub(crate) fn new(strictness: Strictness) -> Self {
+		let diagnostics = Vec::new();
+		Self { strictness, diagnostics }
+	}
+
+	/// Reports a recoverable problem. In strict mode, this returns the error
+	/// back to be returned by the caller, and in lenient mode it's recorded as
+	/// a warning and `Ok` is returned, so the caller should recover and carry on
+	pub(crate) fn recoverable<E: Display>(&mut self, path: &str, error: E) -> Result<(), E> {
+		match self.strictness {
+			Strictness::Strict => { Err(error) }
+			Strictness::Lenient => {
+				self.diagnostics.push(load_err::warning(path.into(), error));
+				Ok(())
+			}
+		}
+	}
+
+	/// Records something at `path` that failed to load. The caller should skip
+	/// it and carry on loading the rest (and can record the returned diagnostic
+	/// on its parent too).
+	#[inline]
+	pub(crate) fn error<E: Display>(&mut self, path: String, error: E) -> &Diagnostic {
+		self.diagnostics.push(load_err::error(path, error));
+		self.diagnostics.last().expect("just pushed")
+	}
+
+	#[inline]
+	pub(crate) fn into_diagnostics(self) -> Vec<Diagnostic> {
+		self.diagnostics
+	}
+
+	/// Finishes loading, returning what was collected. In strict mode, if
+	/// anything failed to load, this is an error with everything that was found.
+	pub(crate) fn finish(self) -> Result<Vec<Diagnostic>, LoadFailed> {
+		let failed = self.strictness == Strictness::Strict
+			&& self.diagnostics.iter().any(|d| d.severity() == Severity::Error);
+		match failed {
+			true => { Err(load_err::load_failed(self.diagnostics)) }
+			false => { Ok(self.diagnostics) }
+		}
+	}
+
+	/// Adds what was collected by a context from [`load_concurrently`]
+	#[inline]
+	pub(crate) fn merge(&mut self, other: Self) {
+		self.diagnostics.extend(other.diagnostics);
+	}
+}
+
+/// Reads the names of the entries in `dir` (ex. the textures in a pack), to
+/// load with [`load_concurrently`]. If it can't be read, that's recorded (on
+/// `dir`) and there are no entries, so the rest of the pack can still load.
+/// Entries with names that aren't UTF-8 are recorded and skipped.
+pub(crate) async fn read_entry_names(ctx: &mut LoadContext, dir: String) -> Vec<String> {
+	let non_utf8 = |path: PathBuf| {
+		ctx.error(path.to_string_lossy().into_owned(), util_err::Error::NonUtf8Path);
+	};
+	match fs::read_dir_names2(dir.clone(), non_utf8).await {
+		Ok(names) => { names }
+		Err(e) => {
+			ctx.error(dir, e);
+			Vec::new()
+		}
+	}
+}
+
+/// Loads sibling entries (textures in a pack, options in a texture, etc)
+/// concurrently, with `f` getting a context of its own for each. Results come
+/// back in the same order as `names` along with the contexts, which should be
+/// merged back in that order, so nothing depends on which finishes first.
+///
+/// There's no limit here, since entries are nested, and a limit at each level
+/// multiplies. Instead every filesystem call goes through one shared limit
+/// (see [`fs2::blocking_permit`]), so the whole walk only does
+/// [`CONCURRENCY_LIMIT`](fs2::CONCURRENCY_LIMIT) at once.
+pub(crate) async fn load_concurrently<'h, T, F, Fu>(
+	ctx: &LoadContext,
+	names: &'h [String],
+	f: F
+) -> Vec<(T, LoadContext)>
+where
+	F: Fn(&'h str, LoadContext) -> Fu,
+	Fu: Future<Output = (T, LoadContext)>
+{
+	let strictness = ctx.strictness;
+	let loads = names.iter().map(|name| f(name, LoadContext::new(strictness)));
+	future::join_all(loads).await
+}
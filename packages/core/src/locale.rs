@@ -0,0 +1,125 @@
+//! Translatable strings in manifests, and picking a translation for the user's
+//! preferred locales
+
+use ::nominal::Nominal;
+use ::schemars::JsonSchema;
+use ::serde::{ Deserialize, Serialize };
+use ::std::collections::BTreeMap;
+
+/// Locale tried after all the user's preferred ones, same as the app's default
+const FALLBACK_LOCALE: &str = "en";
+
+/// A string in a manifest (ex. a name or description) that can be translated.
+/// Either a plain string, used for every locale, or a map from locale (ex.
+/// `"en"`, `"en-GB"`) to the string for that locale, like
+/// `{ "en": "Stone", "de": "Stein" }`
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Eq, Serialize)]
+#[serde(try_from = "LocalisedMeta", untagged)]
+pub enum Localised {
+	Plain(String),
+	Translated(BTreeMap<String, String>)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LocalisedMeta {
+	Plain(String),
+	Translated(BTreeMap<String, String>)
+}
+
+impl TryFrom<LocalisedMeta> for Localised {
+	type Error = &'static str;
+
+	fn try_from(meta: LocalisedMeta) -> Result<Self, Self::Error> {
+		match meta {
+			LocalisedMeta::Plain(s) => { Ok(Self::Plain(s)) }
+			LocalisedMeta::Translated(t) if t.is_empty() => { Err("translations cannot be empty") }
+			LocalisedMeta::Translated(t) => { Ok(Self::Translated(t)) }
+		}
+	}
+}
+
+impl Localised {
+	/// Picks the string for the first locale in `locales` that has one. For
+	/// each locale, an exact match is tried first, then less specific versions
+	/// of it (`zh-Hant-TW`, then `zh-Hant`, then `zh`). If none of them match,
+	/// falls back to English, then to the first translation there is, or an
+	/// empty string if there are none (which manifests can't have).
+	pub fn get<S: AsRef<str>>(&self, locales: &[S]) -> &str {
+		let translations = match self {
+			Self::Plain(s) => { return s }
+			Self::Translated(t) => { t }
+		};
+
+		let find = |locale: &str| translations.iter()
+			.find(|(l, _)| l.eq_ignore_ascii_case(locale))
+			.map(|(_, s)| &**s);
+
+		locales.iter()
+			.map(AsRef::as_ref)
+			.chain([FALLBACK_LOCALE])
+			.flat_map(less_specific)
+			.find_map(find)
+			.or_else(|| translations.values().next().map(|s| &**s))
+			.unwrap_or_default()
+	}
+}
+
+/// Names and descriptions were plain strings in older manifests, which are
+/// used for every locale
+pub(crate) fn localise_plain<PN, PD, N, D>(
+	name: Nominal<String, PN>,
+	description: Option<Nominal<String, PD>>
+) -> (Nominal<Localised, N>, Option<Nominal<Localised, D>>) {
+	let name = name.map_nom(Localised::Plain).transmute_nom();
+	let description = description.map(|d| d.map_nom(Localised::Plain).transmute_nom());
+	(name, description)
+}
+
+/// `locale`, then it with subtags removed from the end one at a time
+fn less_specific(locale: &str) -> impl Iterator<Item = &str> {
+	let mut next = Some(locale);
+	::std::iter::from_fn(move || {
+		let current = next?;
+		next = current.rsplit_once('-').map(|(rest, _)| rest);
+		Some(current)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::ron;
+
+	fn translated() -> Localised {
+		ron::from_str(r#"{ "en": "Stone", "en-GB": "Stone, but British", "zh-Hant": "石頭" }"#).unwrap()
+	}
+
+	#[test]
+	fn plain_is_used_for_every_locale() {
+		let plain = ron::from_str::<Localised>(r#""Stone""#).unwrap();
+		assert_eq!("Stone", plain.get(&["de", "en"]));
+		assert_eq!("Stone", plain.get::<&str>(&[]));
+	}
+
+	#[test]
+	fn picks_best_match_along_locale_chain() {
+		let t = translated();
+		assert_eq!("Stone, but British", t.get(&["en-gb"]));
+		assert_eq!("Stone", t.get(&["en-US"]));
+		assert_eq!("石頭", t.get(&["zh-Hant-TW", "en"]));
+		assert_eq!("石頭", t.get(&["de", "zh-Hant"]));
+		assert_eq!("Stone", t.get(&["de"]));
+
+		let no_english = ron::from_str::<Localised>(r#"{ "fr": "Pierre", "de": "Stein" }"#).unwrap();
+		assert_eq!("Stein", no_english.get(&["ja"]));
+	}
+
+	#[test]
+	fn empty_translations_are_rejected() {
+		assert!(ron::from_str::<Localised>("{}").is_err());
+
+		// but can still be made in code
+		assert_eq!("", Localised::Translated(BTreeMap::new()).get(&["en"]));
+	}
+}
@@ -0,0 +1,232 @@
+//! Upgrading manifests in pack sources to the latest `meta_version`s
+
+use crate::{ option2, pack2, provider2, texture2, workspace2 };
+use crate::error::common::ManifestError;
+use crate::error::migrate_err::{ self, UpgradeSource };
+use crate::util::create_path_builder3;
+use crate::util::{ fs, fs2 };
+
+/// A manifest that was upgraded (or would be, in a dry run)
+#[derive(Debug)]
+pub struct Upgrade {
+	path: String,
+	before: String,
+	after: String
+}
+
+impl Upgrade {
+	#[inline]
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	#[inline]
+	pub fn before(&self) -> &str {
+		&self.before
+	}
+
+	#[inline]
+	pub fn after(&self) -> &str {
+		&self.after
+	}
+
+	/// Line by line diff from the old manifest to the new one, in the style of
+	/// a unified diff (but with the whole file as one hunk)
+	pub fn diff(&self) -> String {
+		let mut diff = format!("--- {path}\n+++ {path}\n", path = self.path);
+
+		for line in ::diff::lines(&self.before, &self.after) {
+			let (prefix, line) = match line {
+				::diff::Result::Left(l) => { ('-', l) }
+				::diff::Result::Both(l, _) => { (' ', l) }
+				::diff::Result::Right(r) => { ('+', r) }
+			};
+			diff.push(prefix);
+			diff.push_str(line);
+			diff.push('\n');
+		}
+
+		diff
+	}
+}
+
+/// Upgrades every manifest in the pack source in `dir` that isn't on the
+/// latest `meta_version` already, returning what was upgraded. Manifests that
+/// are already up to date aren't touched, so comments and formatting in them
+/// are kept. If `dry_run` is true, nothing is written.
+///
+/// All manifests are upgraded in memory before anything is written, so if one
+/// of them can't be upgraded, none of them are. They're then written to
+/// temporary files next to the manifests, and only moved into place once all
+/// of them are written, so a failed write leaves the source as it was (moving
+/// them can still fail partway, but only if the filesystem does).
+pub async fn upgrade_pack_source(dir: &str, dry_run: bool) -> Result<Vec<Upgrade>, UpgradeSource> {
+	let mut upgrades = Vec::new();
+	let p = create_path_builder3().with_root_dir(dir);
+
+	upgrade(&mut upgrades, p.root_manifest_unchecked(), pack2::upgrade_pack).await?;
+
+	for texture_id in dir_names(p.texture_entries_dir_unchecked()).await? {
+		let p = p.clone().with_texture_id(&texture_id);
+		upgrade(&mut upgrades, p.texture_manifest_unchecked(), texture2::upgrade_texture).await?;
+
+		for option_id in dir_names(p.texture_dir_unchecked()).await? {
+			let p = p.clone().with_option_id(&option_id);
+			upgrade(&mut upgrades, p.option_manifest_unchecked(), option2::upgrade_option).await?;
+
+			for provider_id in dir_names(p.option_dir_unchecked()).await? {
+				let p = p.clone().with_provider_id(&provider_id);
+				upgrade(&mut upgrades, p.provider_manifest_unchecked(), provider2::upgrade_provider).await?;
+			}
+		}
+	}
+
+	if !dry_run { write_upgrades(&upgrades).await? }
+
+	Ok(upgrades)
+}
+
+/// Upgrades a workspace config (as from [`WorkspaceRuntime::to_config_str`]) to
+/// the latest `meta_version`, or `None` if it already is the latest
+///
+/// [`WorkspaceRuntime::to_config_str`]: crate::workspace2::WorkspaceRuntime::to_config_str
+#[inline]
+pub fn upgrade_workspace_config(config: &str) -> Result<Option<String>, workspace2::Error> {
+	workspace2::upgrade_workspace(config)
+}
+
+/// Upgrades the manifest at `path` if it exists (directories without one are
+/// skipped when loading too)
+async fn upgrade<F, E>(upgrades: &mut Vec<Upgrade>, path: String, f: F) -> Result<(), UpgradeSource>
+where
+	F: FnOnce(&str) -> Result<Option<String>, E>,
+	E: Into<ManifestError>
+{
+	if !fs2::is_file(|| path.clone()).await? { return Ok(()) }
+
+	let before = fs2::read_to_string(|| path.clone())
+		.await
+		.map_err(|e| migrate_err::read(path.clone(), e))?;
+	let mut after = match f(&before) {
+		Ok(Some(after)) => { after }
+		Ok(None) => { return Ok(()) }
+		Err(e) => { return Err(migrate_err::manifest(path, e)) }
+	};
+	if !after.ends_with('\n') { after.push('\n') }
+
+	upgrades.push(Upgrade { path, before, after });
+	Ok(())
+}
+
+/// Writes upgraded manifests to temporary files, then moves them into place
+/// once all of them are written. If one can't be written, the ones that were
+/// are removed again.
+async fn write_upgrades(upgrades: &[Upgrade]) -> Result<(), UpgradeSource> {
+	let temp_path = |upgrade: &Upgrade| format!("{}.upgrade", upgrade.path);
+
+	for (i, upgrade) in upgrades.iter().enumerate() {
+		let written = fs2::write(|| temp_path(upgrade), upgrade.after.clone().into_bytes()).await;
+		if let Err(e) = written {
+			for upgrade in &upgrades[..=i] {
+				let _ = fs2::remove_file(|| temp_path(upgrade)).await;
+			}
+			return Err(e.into())
+		}
+	}
+
+	for upgrade in upgrades {
+		fs2::rename(|| temp_path(upgrade), || upgrade.path.clone()).await?;
+	}
+
+	Ok(())
+}
+
+/// Names of the directories in `path`, sorted, or none if `path` isn't a directory
+async fn dir_names(path: String) -> Result<Vec<String>, UpgradeSource> {
+	if !fs2::is_dir(|| path.clone()).await? { return Ok(Vec::new()) }
+
+	let mut non_utf8 = None;
+	let names = fs::read_dir_names2(path.clone(), |p| { non_utf8.get_or_insert(p); })
+		.await
+		.map_err(|e| migrate_err::read_dir(path.clone(), e))?;
+	if let Some(p) = non_utf8 {
+		return Err(migrate_err::non_utf8_path(p.to_string_lossy().into_owned()))
+	}
+
+	let mut dirs = Vec::with_capacity(names.len());
+	for name in names {
+		if fs2::is_dir(|| format!("{path}/{name}")).await? { dirs.push(name) }
+	}
+
+	Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_util;
+	use crate::workspace2::{ self, WorkspaceRuntime };
+
+	async fn frontend_data(dir: &str) -> ::serde_json::Value {
+		let mut workspace = WorkspaceRuntime::new(workspace2::nr::Name::new("uwu".into()));
+		workspace.add_pack(dir).await.unwrap();
+		let mc_version = test_util::mc_version("1.20.4");
+		::serde_json::to_value(workspace2::FrontendData::new(&workspace, mc_version, &[])).unwrap()
+	}
+
+	#[tokio::test]
+	async fn upgrade_pack_source_keeps_meaning() {
+		let scratch = test_util::scratch_copy("basic", "upgrade");
+		let dir = scratch.to_str().unwrap();
+		let before = frontend_data(dir).await;
+
+		let pack_manifest = scratch.join("pack.wiwimeta");
+		let original = ::std::fs::read_to_string(&pack_manifest).unwrap();
+
+		let upgrades = upgrade_pack_source(dir, true).await.unwrap();
+		let paths = upgrades.iter()
+			.map(|u| u.path().strip_prefix(dir).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(vec![
+			"/pack.wiwimeta",
+			"/textures/dirt/texture.wiwimeta",
+			"/textures/dirt/handmade/option.wiwimeta",
+			"/textures/dirt/handmade/all/provider.wiwimeta",
+			"/textures/stone/texture.wiwimeta",
+			"/textures/stone/plain/option.wiwimeta",
+			"/textures/stone/plain/all/provider.wiwimeta",
+			"/textures/stone/random/option.wiwimeta",
+			"/textures/stone/random/all/provider.wiwimeta"
+		], paths);
+		assert!(upgrades[0].diff().contains("\n-\tmeta_version: \"1\",\n"));
+		assert!(upgrades[0].diff().contains("\n+\tmeta_version: \"3\",\n"));
+
+		// dry run, so nothing changed yet
+		assert_eq!(original, ::std::fs::read_to_string(&pack_manifest).unwrap());
+
+		upgrade_pack_source(dir, false).await.unwrap();
+		assert_ne!(original, ::std::fs::read_to_string(&pack_manifest).unwrap());
+		assert_eq!(before, frontend_data(dir).await);
+
+		// temporary files were all moved into place
+		assert!(!scratch.join("pack.wiwimeta.upgrade").exists());
+
+		// everything's on the latest version now
+		assert!(upgrade_pack_source(dir, false).await.unwrap().is_empty());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn upgrade_workspace_config_keeps_meaning() {
+		let config = r#"(meta_version:"2",name:"uwu",packs:[],selections:[],mc_version:Some("1.20.4"))"#;
+		let before = WorkspaceRuntime::from_config_str(config).await.unwrap();
+
+		let upgraded = upgrade_workspace_config(config).unwrap().unwrap();
+		assert!(upgraded.contains(r#"meta_version:"3""#));
+		let after = WorkspaceRuntime::from_config_str(&upgraded).await.unwrap();
+		assert_eq!(before.to_config_str().unwrap(), after.to_config_str().unwrap());
+
+		assert!(upgrade_workspace_config(&upgraded).unwrap().is_none());
+	}
+}
@@ -9,5 +9,8 @@ pub enum Error {
 	UtilError(#[from] crate::util::error::Error),
 
 	#[error(transparent)]
-	ProviderError(#[from] crate::provider2::Error)
+	ProviderError(#[from] crate::provider2::Error),
+
+	#[error("error writing manifest:\n{}", ::ts_result::NiceErrorMessage::to_error_message(.0))]
+	SerialiseError(crate::error::ron_err::Ron)
 }
@@ -1,28 +1,73 @@
-use crate::util::ron;
+use crate::locale::{ localise_plain, Localised };
+use crate::util::{ ron, ron2 };
 use super::error::*;
 use super::nm;
+use ::schemars::{ schema::RootSchema, schema_for, JsonSchema };
 use ::serde::{ Deserialize, Serialize };
 
-#[derive(Deserialize, Serialize)]
+/// Manifest of an option (`option.wiwimeta`)
+#[derive(Deserialize, JsonSchema, Serialize)]
 #[serde(tag = "meta_version")]
 enum OptionMeta {
 	#[serde(rename = "1")]
 	Version1 {
+		name: nm::PlainName,
+		description: Option<nm::PlainDescription>
+	},
+	/// same as version 1, but `name` and `description` can be translated
+	#[serde(rename = "2")]
+	Version2 {
 		name: nm::Name,
-		description: nm::Description
+		description: Option<nm::Description>
 	}
 }
 
 pub(super) struct OptionUnversioned {
 	pub(super) name: nm::Name,
-	pub(super) description: nm::Description
+	pub(super) description: Option<nm::Description>
 }
 
 pub(super) fn deserialise_option(s: &str) -> Result<OptionUnversioned> {
+	Ok(unversion(ron::from_str(s)?))
+}
+
+/// Writes a manifest, using the latest `meta_version`
+pub(super) fn serialise_option(option: OptionUnversioned) -> Result<String> {
+	let OptionUnversioned { name, description } = option;
+	let option = OptionMeta::Version2 { name, description };
+	ron2::to_string_pretty(&option).map_err(Error::SerialiseError)
+}
+
+/// Manifest for a new option, with nothing but its name set
+pub(crate) fn new_option(name: &str) -> Result<String> {
+	serialise_option(OptionUnversioned {
+		name: nm::Name::new(Localised::Plain(name.into())),
+		description: None
+	})
+}
+
+/// Upgrades a manifest to the latest `meta_version`, or `None` if it
+/// already is the latest
+pub(crate) fn upgrade_option(s: &str) -> Result<Option<String>> {
+	let meta = ron::from_str(s)?;
+	if let OptionMeta::Version2 { .. } = meta { return Ok(None) }
+	serialise_option(unversion(meta)).map(Some)
+}
+
+/// JSON schema of the manifest, covering every `meta_version`
+pub(crate) fn option_schema() -> RootSchema {
+	schema_for!(OptionMeta)
+}
+
+fn unversion(meta: OptionMeta) -> OptionUnversioned {
 	use OptionMeta::*;
-	Ok(match ron::from_str(s)? {
+	match meta {
 		Version1 { name, description } => {
+			let (name, description) = localise_plain(name, description);
 			OptionUnversioned { name, description }
 		}
-	})
+		Version2 { name, description } => {
+			OptionUnversioned { name, description }
+		}
+	}
 }
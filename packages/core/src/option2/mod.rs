@@ -5,19 +5,24 @@ mod meta;
 mod rt;
 
 pub use self::error::Error;
+pub(crate) use self::meta::{ new_option, option_schema, upgrade_option };
 pub use self::rt::{ OptionRuntime, FrontendData };
-use super::provider2;
-use ::hashbrown::HashMap;
+use super::{ error::load_err, provider2 };
+use super::locale::Localised;
+use ::std::collections::BTreeMap;
 
 ::nominal::nominal_mod! {
 	mod nm {
-		nominal!(pub Name, inner: String);
-		nominal!(pub Description, inner: Option<String>);
+		nominal!(pub PlainName, inner: String);
+		nominal!(pub PlainDescription, inner: String);
+		nominal!(pub Name, inner: Localised);
+		nominal!(pub Description, inner: Localised);
 	}
 	pub mod nr {
-		nominal!(pub Name, inner: String);
-		nominal!(pub Description, inner: Option<String>);
+		nominal!(pub Name, inner: Localised);
+		nominal!(pub Description, inner: Option<Localised>);
 		nominal!(pub ID, inner: String);
-		nominal!(pub Providers, inner: HashMap<provider2::nr::ID, provider2::ProviderRuntime>);
+		nominal!(pub Providers, inner: BTreeMap<provider2::nr::ID, provider2::ProviderRuntime>);
+		nominal!(pub Errors, inner: Vec<load_err::Diagnostic>);
 	}
 }
@@ -1,21 +1,26 @@
 use ::mc_versions::MCVersionRef;
+use crate::load::{ load_concurrently, read_entry_names, LoadContext };
 use crate::util::fs;
+use crate::error::load_err::Diagnostic;
 use crate::util::path_builder3::WithOptionID;
+use crate::watch;
 use crate::provider2::{ self, ProviderRuntime };
 use super::error::*;
-use super::{ meta, nr };
-use ::hashbrown::HashMap;
+use super::{ meta, nm, nr };
+use ::std::collections::BTreeMap;
 use ::serde::Serialize;
 
 pub struct OptionRuntime {
 	name: nr::Name,
 	description: nr::Description,
 	id: nr::ID,
-	providers: nr::Providers
+	providers: nr::Providers,
+	/// providers in this option that failed to load
+	errors: nr::Errors
 }
 
 impl OptionRuntime {
-	pub(crate) async fn new(p: &WithOptionID<'_>) -> Result<Option<Self>> {
+	pub(crate) async fn new(p: &WithOptionID<'_>, ctx: &mut LoadContext) -> Result<Option<Self>> {
 		let dir = p.option_dir_silent_fail().await?;
 		let meta_path = p.option_manifest_silent_fail().await?;
 		let meta_file = fs::read_to_string2(meta_path).await?;
@@ -25,52 +30,124 @@ impl OptionRuntime {
 		} = meta::deserialise_option(&meta_file)?;
 
 		let name = name.transmute_nom();
-		let description = description.transmute_nom();
+		let description = nr::Description::new(description.map(nm::Description::into_inner));
 		let id = nr::ID::new(p.option_id_ref().into());
 
-		let providers = read_providers(p).await?;
+		let (providers, errors) = read_providers(p, ctx).await;
 
 		Ok(Some(Self {
 			name,
 			description,
 			id,
-			providers
+			providers,
+			errors
 		}))
 	}
+
+	#[inline]
+	pub fn name(&self) -> &nr::Name {
+		&self.name
+	}
+
+	#[inline]
+	pub fn description(&self) -> &nr::Description {
+		&self.description
+	}
+
+	#[inline]
+	pub fn id(&self) -> &nr::ID {
+		&self.id
+	}
+
+	#[inline]
+	pub fn providers(&self) -> &nr::Providers {
+		&self.providers
+	}
+
+	#[inline]
+	pub fn errors(&self) -> &nr::Errors {
+		&self.errors
+	}
+
+	/// Replaces the provider `id` (in `dir`) with what was just loaded: the
+	/// provider, `None` if it's not there anymore, or the error if it failed
+	/// to load
+	pub(crate) fn replace_provider(
+		&mut self,
+		id: &provider2::nr::ID,
+		dir: &str,
+		loaded: Result<Option<ProviderRuntime>, Diagnostic>
+	) {
+		let errors = self.errors.mut_inner();
+		errors.retain(|d| !watch::is_in_dir(d.path(), dir));
+
+		let providers = self.providers.mut_inner();
+		match loaded {
+			Ok(Some(v)) => { providers.insert(id.clone(), v); }
+			Ok(None) => { providers.remove(id); }
+			Err(e) => {
+				providers.remove(id);
+				errors.push(e);
+			}
+		}
+	}
+
+	/// Gets the provider to use for the provided MC version. If more than one
+	/// is available, the one with the (lexicographically) smallest ID is used,
+	/// so the choice doesn't depend on the order providers were read in.
+	pub fn provider_for(&self, mc_version: MCVersionRef) -> Option<&ProviderRuntime> {
+		self.providers.ref_inner()
+			.values()
+			.find(|p| p.is_available_for(mc_version))
+	}
 }
 
-async fn read_providers(p: &WithOptionID<'_>) -> Result<nr::Providers> {
-	let version_entries_dir = p.provider_entries_dir_checked().await?;
+async fn read_providers(p: &WithOptionID<'_>, ctx: &mut LoadContext) -> (nr::Providers, nr::Errors) {
 	let mut versions_nom = nr::Providers::default();
 	let versions = versions_nom.mut_inner();
-	let mut read_dir = fs::read_dir2(version_entries_dir).await?;
-
-	while let Some(file) = read_dir.next().await? {
-		let file_name = file.file_name();
-		let p = p.clone().with_provider_id_osstr(&file_name)?;
+	let mut errors = Vec::new();
+	let provider_ids = match p.provider_entries_dir_checked().await {
+		Ok(dir) => { read_entry_names(ctx, dir).await }
+		Err(e) => {
+			ctx.error(p.option_dir_unchecked(), e);
+			Vec::new()
+		}
+	};
+	let results = load_concurrently(ctx, &provider_ids, |provider_id, mut ctx| async move {
+		let p = p.clone().with_provider_id(provider_id);
+		let result = ProviderRuntime::new(&p, &mut ctx).await;
+		((p, result), ctx)
+	}).await;
 
-		// TODO
-		if let Some(v) = ProviderRuntime::new(&p).await? {
-			let id = provider2::nr::ID::new(p.provider_id_ref().into());
-			versions.insert(id, v);
+	for ((p, result), provider_ctx) in results {
+		ctx.merge(provider_ctx);
+		match result {
+			Ok(Some(v)) => {
+				let id = provider2::nr::ID::new(p.provider_id_ref().into());
+				versions.insert(id, v);
+			}
+			Ok(None) => {}
+			Err(e) => { errors.push(ctx.error(p.provider_dir_unchecked(), e).clone()) }
 		}
 	}
 
-	Ok(versions_nom)
+	(versions_nom, nr::Errors::new(errors))
 }
 
 #[derive(Serialize)]
 pub struct FrontendData<'h> {
-	name: &'h nr::Name,
-	description: &'h nr::Description,
+	name: &'h str,
+	description: Option<&'h str>,
 	id: &'h nr::ID,
-	available_providers: HashMap<&'h str, provider2::FrontendData<'h>>
+	available_providers: BTreeMap<&'h str, provider2::FrontendData<'h>>,
+	/// providers that failed to load, so they can be shown as broken
+	errors: &'h nr::Errors
 }
 
 impl<'h> FrontendData<'h> {
-	pub fn new(option: &'h OptionRuntime, mc_version: MCVersionRef) -> Self {
-		let name = &option.name;
-		let description = &option.description;
+	pub fn new(option: &'h OptionRuntime, mc_version: MCVersionRef, locales: &[String]) -> Self {
+		let name = option.name.ref_inner().get(locales);
+		let description = option.description.ref_inner().as_ref().map(|d| d.get(locales));
 		let id = &option.id;
 		let available_providers = option.providers.ref_inner()
 			.iter()
@@ -79,7 +156,8 @@ impl<'h> FrontendData<'h> {
 					.map(|p| (&**id.ref_inner(), p))
 			})
 			.collect();
+		let errors = &option.errors;
 
-		Self { name, description, id, available_providers }
+		Self { name, description, id, available_providers, errors }
 	}
 }
@@ -21,12 +21,50 @@ pub enum Error {
 	)]
 	DepsNotSatisfied(Vec<(nr::ID, ::semver::VersionReq, Option<::semver::Version>)>),
 
+	#[error("texture \"{texture_id}\" extends a texture in pack \"{pack_id}\", which isn't a dependency")]
+	ExtendsNonDependency {
+		texture_id: String,
+		pack_id: String
+	},
+
+	#[error("texture \"{texture_id}\" extends \"{pack_id}:{extends_texture_id}\", which doesn't exist")]
+	ExtendsUnknownTexture {
+		texture_id: String,
+		pack_id: String,
+		extends_texture_id: String
+	},
+
+	#[error("texture \"{texture_id}\" extends \"{pack_id}:{extends_texture_id}\", which extends another texture itself (extend that one instead)")]
+	ExtendsExtendingTexture {
+		texture_id: String,
+		pack_id: String,
+		extends_texture_id: String
+	},
+
+	#[error(
+		"incompatible with loaded packs: {}",
+		.0.iter()
+			.map(|(id, req, v)| match v {
+				Some(v) => { format!("{id} {req} ({v} loaded)") }
+				None => { format!("{id} {req} (loaded, with no version)") }
+			})
+			.collect::<Vec<_>>()
+			.join(", ")
+	)]
+	Incompatible(Vec<(nr::ID, ::semver::VersionReq, Option<::semver::Version>)>),
+
+	#[error(transparent)]
+	LoadFailed(#[from] crate::error::load_err::LoadFailed),
+
 	#[error("non UTF-8 paths are not supported")]
 	NonUtf8Path,
 
 	#[error("error parsing semver:\n{0}")]
 	SemverParseError(#[from] ::semver::Error),
 
+	#[error("error writing manifest:\n{}", ::ts_result::NiceErrorMessage::to_error_message(.0))]
+	SerialiseError(crate::error::ron_err::Ron),
+
 	#[error(transparent)]
 	TextureError(#[from] crate::texture2::Error),
 
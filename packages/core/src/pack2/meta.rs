@@ -1,34 +1,167 @@
-use crate::util::ron;
+use crate::locale::{ localise_plain, Localised };
+use crate::util::{ ron, ron2 };
 use super::error::*;
 use super::nm;
+use ::schemars::{ schema::RootSchema, schema_for, JsonSchema };
 use ::serde::{ Deserialize, Serialize };
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Manifest of a pack (`pack.wiwimeta`)
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(tag = "meta_version")]
 enum PackMeta {
 	#[serde(rename = "1")]
 	Version1 {
+		name: nm::PlainName,
+		description: Option<nm::PlainDescription>,
+		id: nm::ID,
+		version: Option<nm::Version>,
+		dependencies: Option<nm::Dependencies>
+	},
+	#[serde(rename = "2")]
+	Version2 {
+		name: nm::PlainName,
+		description: Option<nm::PlainDescription>,
+		id: nm::ID,
+		version: Option<nm::Version>,
+		dependencies: Option<nm::Dependencies>,
+		#[serde(default)]
+		optional_dependencies: Option<nm::OptionalDependencies>,
+		#[serde(default)]
+		incompatible_with: Option<nm::IncompatibleWith>
+	},
+	/// same as version 2, but `name` and `description` can be translated
+	#[serde(rename = "3")]
+	Version3 {
 		name: nm::Name,
-		description: nm::Description,
+		description: Option<nm::Description>,
 		id: nm::ID,
-		version: nm::Version,
-		dependencies: nm::Dependencies
+		version: Option<nm::Version>,
+		dependencies: Option<nm::Dependencies>,
+		#[serde(default)]
+		optional_dependencies: Option<nm::OptionalDependencies>,
+		#[serde(default)]
+		incompatible_with: Option<nm::IncompatibleWith>
 	}
 }
 
 pub(super) struct PackUnversioned {
 	pub(super) name: nm::Name,
-	pub(super) description: nm::Description,
+	pub(super) description: Option<nm::Description>,
 	pub(super) id: nm::ID,
-	pub(super) version: nm::Version,
-	pub(super) dependencies: nm::Dependencies
+	pub(super) version: Option<nm::Version>,
+	pub(super) dependencies: Option<nm::Dependencies>,
+	pub(super) optional_dependencies: Option<nm::OptionalDependencies>,
+	pub(super) incompatible_with: Option<nm::IncompatibleWith>
 }
 
 pub(super) fn deserialise_pack(s: &str) -> Result<PackUnversioned> {
+	Ok(unversion(ron::from_str(s)?))
+}
+
+/// Writes a manifest, using the latest `meta_version`
+pub(super) fn serialise_pack(pack: PackUnversioned) -> Result<String> {
+	let PackUnversioned {
+		name,
+		description,
+		id,
+		version,
+		dependencies,
+		optional_dependencies,
+		incompatible_with
+	} = pack;
+	let pack = PackMeta::Version3 {
+		name,
+		description,
+		id,
+		version,
+		dependencies,
+		optional_dependencies,
+		incompatible_with
+	};
+	ron2::to_string_pretty(&pack).map_err(Error::SerialiseError)
+}
+
+/// Manifest for a new pack, with nothing but its ID, name and description set
+pub(crate) fn new_pack(id: &str, name: &str, description: Option<&str>) -> Result<String> {
+	serialise_pack(PackUnversioned {
+		name: nm::Name::new(Localised::Plain(name.into())),
+		description: description.map(|d| nm::Description::new(Localised::Plain(d.into()))),
+		id: nm::ID::new(id.into()),
+		version: None,
+		dependencies: None,
+		optional_dependencies: None,
+		incompatible_with: None
+	})
+}
+
+/// Upgrades a manifest to the latest `meta_version`, or `None` if it
+/// already is the latest
+pub(crate) fn upgrade_pack(s: &str) -> Result<Option<String>> {
+	let meta = ron::from_str(s)?;
+	if let PackMeta::Version3 { .. } = meta { return Ok(None) }
+	serialise_pack(unversion(meta)).map(Some)
+}
+
+/// JSON schema of the manifest, covering every `meta_version`
+pub(crate) fn pack_schema() -> RootSchema {
+	schema_for!(PackMeta)
+}
+
+fn unversion(meta: PackMeta) -> PackUnversioned {
 	use PackMeta::*;
-	Ok(match ron::from_str(s)? {
+	match meta {
 		Version1 { name, description, id, version, dependencies } => {
-			PackUnversioned { name, description, id, version, dependencies }
+			let (name, description) = localise_plain(name, description);
+			let optional_dependencies = None;
+			let incompatible_with = None;
+			PackUnversioned {
+				name,
+				description,
+				id,
+				version,
+				dependencies,
+				optional_dependencies,
+				incompatible_with
+			}
 		}
-	})
+		Version2 {
+			name,
+			description,
+			id,
+			version,
+			dependencies,
+			optional_dependencies,
+			incompatible_with
+		} => {
+			let (name, description) = localise_plain(name, description);
+			PackUnversioned {
+				name,
+				description,
+				id,
+				version,
+				dependencies,
+				optional_dependencies,
+				incompatible_with
+			}
+		}
+		Version3 {
+			name,
+			description,
+			id,
+			version,
+			dependencies,
+			optional_dependencies,
+			incompatible_with
+		} => {
+			PackUnversioned {
+				name,
+				description,
+				id,
+				version,
+				dependencies,
+				optional_dependencies,
+				incompatible_with
+			}
+		}
+	}
 }
@@ -5,6 +5,7 @@ mod meta;
 mod rt;
 
 pub use self::error::Error;
+pub(crate) use self::meta::{ new_pack, pack_schema, upgrade_pack };
 pub use self::rt::{
 	PackRuntime,
 	Dependency,
@@ -12,25 +13,36 @@ pub use self::rt::{
 	DependencyResult,
 	FrontendData
 };
-use super::texture2;
+use super::{ error::load_err, texture2 };
+use super::locale::Localised;
 use ::hashbrown::HashMap;
+use ::std::collections::BTreeMap;
 
 ::nominal::nominal_mod! {
 	mod nm {
-		nominal!(pub Name, inner: String);
-		nominal!(pub Description, inner: Option<String>);
+		nominal!(pub PlainName, inner: String);
+		nominal!(pub PlainDescription, inner: String);
+		nominal!(pub Name, inner: Localised);
+		nominal!(pub Description, inner: Localised);
 		nominal!(pub ID, inner: String);
-		nominal!(pub Version, inner: Option<String>);
+		nominal!(pub Version, inner: String);
 		nominal!(pub VersionReq, inner: String);
-		nominal!(pub Dependencies, inner: Option<HashMap<ID, VersionReq>>);
+		nominal!(pub Dependencies, inner: BTreeMap<ID, VersionReq>);
+		nominal!(pub OptionalDependencies, inner: BTreeMap<ID, VersionReq>);
+		nominal!(pub IncompatibleWith, inner: BTreeMap<ID, VersionReq>);
 	}
 	pub mod nr {
-		nominal!(pub Name, inner: String);
-		nominal!(pub Description, inner: Option<String>);
+		nominal!(pub Name, inner: Localised);
+		nominal!(pub Description, inner: Option<Localised>);
 		nominal!(pub ID, inner: String);
 		nominal!(pub Dir, inner: String);
+		nominal!(pub AddedDir, inner: String);
 		nominal!(pub Version, inner: Option<::semver::Version>);
-		nominal!(pub Dependencies, inner: HashMap<ID, ::semver::VersionReq>);
-		nominal!(pub Textures, inner: HashMap<texture2::nr::ID, texture2::TextureRuntime>);
+		nominal!(pub Dependencies, inner: BTreeMap<ID, ::semver::VersionReq>);
+		nominal!(pub OptionalDependencies, inner: BTreeMap<ID, ::semver::VersionReq>);
+		nominal!(pub IncompatibleWith, inner: BTreeMap<ID, ::semver::VersionReq>);
+		nominal!(pub Textures, inner: BTreeMap<texture2::nr::ID, texture2::TextureRuntime>);
+		nominal!(pub InactiveTextures, inner: BTreeMap<texture2::nr::ID, texture2::TextureRuntime>);
+		nominal!(pub Diagnostics, inner: Vec<load_err::Diagnostic>);
 	}
 }
@@ -1,11 +1,17 @@
 use ::mc_versions::MCVersionRef;
-use crate::texture2::{ self, TextureRuntime };
+use crate::error::load_err::Diagnostic;
+use crate::load::{ load_concurrently, read_entry_names, LoadContext, Strictness };
+use crate::option2::OptionRuntime;
+use crate::provider2::ProviderRuntime;
+use crate::texture2::{ self, MergedTexture, TextureRuntime };
 use crate::util::{ create_path_builder3, fs };
-use crate::util::path_builder3::WithRootDir;
+use crate::util::path_builder3::{ osstr_to_str, WithRootDir };
+use crate::watch::{ self, Entry };
 use super::error::*;
 use super::{ meta, nm, nr };
 use ::async_trait::async_trait;
 use ::hashbrown::HashMap;
+use ::std::collections::BTreeMap;
 use ::serde::Serialize;
 use ::std::ffi::OsStr;
 
@@ -13,10 +19,19 @@ pub struct PackRuntime {
 	name: nr::Name,
 	description: nr::Description,
 	id: nr::ID,
+	/// canonical, see [`canonical_dir`]
 	dir: nr::Dir,
+	/// the dir as it was given when adding the pack, which is what gets saved
+	added_dir: nr::AddedDir,
 	version: nr::Version,
 	dependencies: nr::Dependencies,
-	textures: nr::Textures
+	optional_dependencies: nr::OptionalDependencies,
+	incompatible_with: nr::IncompatibleWith,
+	textures: nr::Textures,
+	/// textures extending a texture in an optional dependency that isn't
+	/// loaded (yet). They're activated when it's added to the workspace
+	inactive_textures: nr::InactiveTextures,
+	diagnostics: nr::Diagnostics
 }
 
 // TODO: consider removing #[async_trait]
@@ -31,7 +46,12 @@ pub trait DependencyResolver {
 }
 
 #[async_trait]
-pub trait Dependency {}
+pub trait Dependency {
+	fn version(&self) -> Option<&::semver::Version>;
+
+	/// Gets a texture in the dependency, for checking textures that extend it
+	fn texture(&self, texture_id: &texture2::nr::ID) -> Option<&TextureRuntime>;
+}
 
 pub enum DependencyResult<D> {
 	/// Found and satisfies version requirement
@@ -49,31 +69,37 @@ pub enum DependencyResult<D> {
 
 impl PackRuntime {
 	#[inline]
-	pub(crate) async fn new<R, D>(dir: &str, dep_resolver: R)
+	pub(crate) async fn new<R, D>(dir: &str, dep_resolver: R, strictness: Strictness)
 		-> Result<Self>
 	where
 		R: DependencyResolver<Dependency = D>,
 		D: Dependency
 	{
+		let canonical = canonical_dir(dir).await?;
 		let p = create_path_builder3()
-			.with_root_dir(dir);
-		Self::new_with_path_builder(p, dep_resolver).await
+			.with_root_dir(&canonical);
+		Self::new_with_path_builder(p, dir, dep_resolver, strictness).await
 	}
 
 	#[inline]
-	pub(crate) async fn new_with_osstr<R, D>(dir: &OsStr, dep_resolver: R)
+	pub(crate) async fn new_with_osstr<R, D>(dir: &OsStr, dep_resolver: R, strictness: Strictness)
 		-> Result<Self>
 	where
 		R: DependencyResolver<Dependency = D>,
 		D: Dependency
 	{
-		let p = create_path_builder3()
-			.with_root_dir_osstr(dir)?;
-		Self::new_with_path_builder(p, dep_resolver).await
+		Self::new(osstr_to_str(dir)?, dep_resolver, strictness).await
 	}
 
-	pub(crate) async fn new_with_path_builder<R, D>(p: WithRootDir<'_>, dep_resolver: R)
-		-> Result<Self>
+	/// Loads the pack in the root dir of `p`. That dir should be canonical
+	/// (see [`canonical_dir`]), since the paths in file change events are
+	/// matched against it. `added_dir` is the dir as it was given.
+	pub(crate) async fn new_with_path_builder<R, D>(
+		p: WithRootDir<'_>,
+		added_dir: &str,
+		dep_resolver: R,
+		strictness: Strictness
+	) -> Result<Self>
 	where
 		R: DependencyResolver<Dependency = D>,
 		D: Dependency
@@ -87,97 +113,367 @@ impl PackRuntime {
 			description,
 			id,
 			version,
-			dependencies
+			dependencies,
+			optional_dependencies,
+			incompatible_with
 		} = meta::deserialise_pack(&meta_file)?;
 
 		let name = name.transmute_nom();
-		let description = description.transmute_nom();
+		let description = nr::Description::new(description.map(nm::Description::into_inner));
 		let id = id.transmute_nom();
 		let dir = nr::Dir::new(dir);
+		let added_dir = nr::AddedDir::new(added_dir.into());
 		let version = process_version(version)?;
-		let dependencies = process_deps(dependencies, &dep_resolver).await?;
-
-		// TODO: do something with dependencies in hashmap when actual logic is in
-		// I think we'll need to pass to read_textures to process it
-		// a texture can extend a texture on a dependency, give it more options
-		// (or maybe modify existing options??)
-		let dependencies = dependencies.into_iter()
-			.map(|(id, (_, req))| (id, req))
-			.collect();
-		let dependencies = nr::Dependencies::new(dependencies);
+		let dependencies = parse_reqs(dependencies.map(nm::Dependencies::into_inner))?;
+		let optional_dependencies = parse_reqs(optional_dependencies.map(nm::OptionalDependencies::into_inner))?;
+		let incompatible_with = parse_reqs(incompatible_with.map(nm::IncompatibleWith::into_inner))?;
+
+		check_incompatible(&incompatible_with, &dep_resolver).await?;
+		let mut resolved = process_deps(&dependencies, &dep_resolver, false).await?;
+		let resolved_optional = process_deps(&optional_dependencies, &dep_resolver, true).await?;
+
+		let mut ctx = LoadContext::new(strictness);
+		// in lenient mode, textures, options and providers that fail to load
+		// are left out (and recorded), so the rest of the pack can still be used
+		let mut textures = read_textures(&p, &mut ctx).await;
+		let diagnostics = nr::Diagnostics::new(ctx.finish()?);
+
+		// textures extending a texture in an optional dependency are only
+		// active if that dependency is there
+		let (active, inactive) = ::std::mem::take(textures.mut_inner())
+			.into_iter()
+			.partition(|(_, t)| match t.extends().ref_inner() {
+				Some(e) => {
+					!optional_dependencies.contains_key(e.pack_id())
+						|| resolved_optional.contains_key(e.pack_id())
+				}
+				None => { true }
+			});
+		*textures.mut_inner() = active;
+		let inactive_textures = nr::InactiveTextures::new(inactive);
+
+		// textures can extend textures in dependencies (giving them more options),
+		// so the dependencies are needed to check those, but not after that
+		resolved.extend(resolved_optional);
+		check_extends(textures.ref_inner().values(), &resolved)?;
 
-		let textures = read_textures(&p).await?;
+		let dependencies = nr::Dependencies::new(dependencies);
+		let optional_dependencies = nr::OptionalDependencies::new(optional_dependencies);
+		let incompatible_with = nr::IncompatibleWith::new(incompatible_with);
 
 		Ok(Self {
 			name,
 			description,
 			id,
 			dir,
+			added_dir,
 			version,
 			dependencies,
-			textures
+			optional_dependencies,
+			incompatible_with,
+			textures,
+			inactive_textures,
+			diagnostics
 		})
 	}
 
+	/// Reads just the ID and the IDs of the dependencies of the pack in the
+	/// provided dir, without loading the pack, so the order packs need to be
+	/// loaded in can be worked out first
+	pub(crate) async fn read_id_and_dependencies(dir: &str)
+		-> Result<(nr::ID, Vec<nr::ID>)>
+	{
+		let p = create_path_builder3()
+			.with_root_dir(dir);
+		let meta_path = p.root_manifest_checked().await?;
+		let meta_file = fs::read_to_string2(meta_path).await?;
+		let meta::PackUnversioned {
+			id,
+			dependencies,
+			optional_dependencies,
+			..
+		} = meta::deserialise_pack(&meta_file)?;
+
+		// optional dependencies that are there need to be loaded first too
+		let id = id.transmute_nom();
+		let mut dependencies = dependencies
+			.map(nm::Dependencies::into_inner)
+			.unwrap_or_default()
+			.into_keys()
+			.chain(optional_dependencies.map(nm::OptionalDependencies::into_inner).unwrap_or_default().into_keys())
+			.map(|id| nr::ID::new(id.into_inner()))
+			.collect::<Vec<_>>();
+		dependencies.sort_unstable();
+		Ok((id, dependencies))
+	}
+
+	#[inline]
+	pub fn name(&self) -> &nr::Name {
+		&self.name
+	}
+
+	#[inline]
+	pub fn description(&self) -> &nr::Description {
+		&self.description
+	}
+
 	#[inline]
 	pub fn id(&self) -> &nr::ID {
 		&self.id
 	}
 
+	/// The pack's dir, absolute and with symlinks resolved
 	#[inline]
 	pub fn dir(&self) -> &nr::Dir {
 		&self.dir
 	}
 
+	/// The pack's dir as it was given when the pack was added (ex. a relative
+	/// path, or one through a symlink)
+	#[inline]
+	pub fn added_dir(&self) -> &nr::AddedDir {
+		&self.added_dir
+	}
+
 	#[inline]
 	pub fn version(&self) -> &nr::Version {
 		&self.version
 	}
+
+	#[inline]
+	pub fn dependencies(&self) -> &nr::Dependencies {
+		&self.dependencies
+	}
+
+	#[inline]
+	pub fn optional_dependencies(&self) -> &nr::OptionalDependencies {
+		&self.optional_dependencies
+	}
+
+	#[inline]
+	pub fn incompatible_with(&self) -> &nr::IncompatibleWith {
+		&self.incompatible_with
+	}
+
+	#[inline]
+	pub fn textures(&self) -> &nr::Textures {
+		&self.textures
+	}
+
+	/// Checks that `dependency`, a pack being added to the workspace after this
+	/// one, can be used as an optional dependency of this pack (if it is one):
+	/// it has to satisfy the version requirement, and the inactive textures
+	/// extending it have to be valid
+	pub(crate) fn check_late_dependency<D: Dependency>(&self, id: &nr::ID, dependency: D) -> Result<()> {
+		let req = match self.optional_dependencies.ref_inner().get(id) {
+			Some(req) => { req }
+			None => { return Ok(()) }
+		};
+
+		if let Some(v) = dependency.version() {
+			if !req.matches(v) {
+				return Err(Error::DepsNotSatisfied(vec![(id.clone(), req.clone(), Some(v.clone()))]))
+			}
+		}
+
+		let extending = self.inactive_textures.ref_inner()
+			.values()
+			.filter(|t| t.extends().ref_inner().as_ref().map(|e| e.pack_id()) == Some(id));
+		let mut dependencies = HashMap::with_capacity(1);
+		dependencies.insert(id.clone(), dependency);
+		check_extends(extending, &dependencies)
+	}
+
+	/// Activates the inactive textures extending textures in the pack `id`,
+	/// which should have been checked with [`check_late_dependency`](Self::check_late_dependency)
+	pub(crate) fn activate_extending(&mut self, id: &nr::ID) {
+		let inactive = ::std::mem::take(self.inactive_textures.mut_inner());
+		let (activated, inactive) = inactive
+			.into_iter()
+			.partition::<BTreeMap<_, _>, _>(|(_, t)| {
+				t.extends().ref_inner().as_ref().map(|e| e.pack_id()) == Some(id)
+			});
+
+		*self.inactive_textures.mut_inner() = inactive;
+		self.textures.mut_inner().extend(activated);
+	}
+
+	/// Pack IDs of the optional dependencies that inactive textures are
+	/// waiting on
+	pub(crate) fn waiting_on(&self) -> impl Iterator<Item = &nr::ID> {
+		self.inactive_textures.ref_inner()
+			.values()
+			.filter_map(|t| t.extends().ref_inner().as_ref())
+			.map(|e| e.pack_id())
+	}
+
+	/// Reloads one texture, option or provider in this pack, leaving the rest
+	/// of it as it is. Returns `false` without changing anything if it can't be
+	/// reloaded on its own, and the entry it's in should be reloaded instead:
+	/// textures extending another (or extended by others, when `extended`) have
+	/// to be checked against other packs, and options and providers need the
+	/// entry they're in to be loaded. In strict mode, if anything in the entry
+	/// fails to load, this errors and the old one is kept.
+	pub(crate) async fn reload_entry(&mut self, entry: &Entry, strictness: Strictness, extended: bool)
+		-> Result<bool>
+	{
+		let dir = self.dir.ref_inner().clone();
+		let p = create_path_builder3().with_root_dir(&dir);
+		let mut ctx = LoadContext::new(strictness);
+
+		match entry {
+			Entry::Pack => { Ok(false) }
+
+			Entry::Texture(texture_id) => {
+				let was_extending = self.textures.ref_inner()
+					.get(texture_id)
+					.or_else(|| self.inactive_textures.ref_inner().get(texture_id))
+					.is_some_and(|t| t.extends().ref_inner().is_some());
+				if was_extending { return Ok(false) }
+
+				let p = p.with_texture_id(texture_id.ref_inner());
+				let texture_dir = p.texture_dir_unchecked();
+				let texture = match TextureRuntime::new(&p, &mut ctx).await {
+					Ok(Some(t)) if t.extends().ref_inner().is_some() => { return Ok(false) }
+					Ok(t) => { t }
+					Err(e) => {
+						ctx.error(texture_dir.clone(), e);
+						None
+					}
+				};
+				if texture.is_none() && extended { return Ok(false) }
+				let diagnostics = ctx.finish()?;
+
+				let textures = self.textures.mut_inner();
+				match texture {
+					Some(t) => { textures.insert(texture_id.clone(), t); }
+					None => { textures.remove(texture_id); }
+				}
+				self.replace_diagnostics(&texture_dir, diagnostics);
+				Ok(true)
+			}
+
+			Entry::Option(texture_id, option_id) => {
+				let texture = match self.textures.mut_inner().get_mut(texture_id) {
+					Some(t) => { t }
+					None => { return Ok(false) }
+				};
+
+				let p = p.with_texture_id(texture_id.ref_inner()).with_option_id(option_id.ref_inner());
+				let option_dir = p.option_dir_unchecked();
+				let loaded = OptionRuntime::new(&p, &mut ctx).await
+					.map_err(|e| ctx.error(option_dir.clone(), e).clone());
+				let diagnostics = ctx.finish()?;
+
+				if !texture.replace_option(option_id, &option_dir, loaded) { return Ok(false) }
+				self.replace_diagnostics(&option_dir, diagnostics);
+				Ok(true)
+			}
+
+			Entry::Provider(texture_id, option_id, provider_id) => {
+				let option = self.textures.mut_inner()
+					.get_mut(texture_id)
+					.and_then(|t| t.option_mut(option_id));
+				let option = match option {
+					Some(o) => { o }
+					None => { return Ok(false) }
+				};
+
+				let p = p.with_texture_id(texture_id.ref_inner())
+					.with_option_id(option_id.ref_inner())
+					.with_provider_id(provider_id.ref_inner());
+				let provider_dir = p.provider_dir_unchecked();
+				let loaded = ProviderRuntime::new(&p, &mut ctx).await
+					.map_err(|e| ctx.error(provider_dir.clone(), e).clone());
+				let diagnostics = ctx.finish()?;
+
+				option.replace_provider(provider_id, &provider_dir, loaded);
+				self.replace_diagnostics(&provider_dir, diagnostics);
+				Ok(true)
+			}
+		}
+	}
+
+	/// Replaces what was found in `dir` before with what was found now
+	fn replace_diagnostics(&mut self, dir: &str, new: Vec<Diagnostic>) {
+		let diagnostics = self.diagnostics.mut_inner();
+		diagnostics.retain(|d| !watch::is_in_dir(d.path(), dir));
+		diagnostics.extend(new);
+	}
+
+	/// Recoverable problems found while loading the pack in lenient mode
+	#[inline]
+	pub fn diagnostics(&self) -> &nr::Diagnostics {
+		&self.diagnostics
+	}
+}
+
+/// Checks that `dir` is a dir, and makes it absolute with symlinks resolved,
+/// so it matches the paths the OS reports when files in it change
+async fn canonical_dir(dir: &str) -> Result<String> {
+	let dir = create_path_builder3()
+		.with_root_dir(dir)
+		.root_dir_checked()
+		.await?;
+	Ok(fs::canonicalize2(dir).await?)
 }
 
 #[inline]
-fn process_version(version: nm::Version) -> Result<nr::Version> {
+fn process_version(version: Option<nm::Version>) -> Result<nr::Version> {
 	version
-		.map_nom_some(|v| ::semver::Version::parse(&v))
-		.map_nom(Option::transpose)
-		.transmute_nom()
+		.map(|v| ::semver::Version::parse(v.ref_inner()))
 		.transpose()
+		.map(nr::Version::new)
 		.map_err(Into::into)
 }
 
 #[inline]
-async fn process_deps<R, D>(deps: nm::Dependencies, dep_resolver: &R)
-	-> Result<HashMap<nr::ID, (D, ::semver::VersionReq)>>
+fn parse_reqs(reqs: Option<BTreeMap<nm::ID, nm::VersionReq>>)
+	-> Result<BTreeMap<nr::ID, ::semver::VersionReq>>
+{
+	reqs.unwrap_or_default()
+		.into_iter()
+		.map(|(id, req)| {
+			let id = nr::ID::new(id.into_inner());
+			let req = ::semver::VersionReq::parse(req.ref_inner())?;
+			Ok((id, req))
+		})
+		.collect()
+}
+
+/// Resolves dependencies, erroring if any of them aren't satisfied. Optional
+/// dependencies that aren't there are left out of the returned map, but ones
+/// that are there with a version that doesn't satisfy the requirement are
+/// still an error.
+async fn process_deps<R, D>(
+	deps: &BTreeMap<nr::ID, ::semver::VersionReq>,
+	dep_resolver: &R,
+	optional: bool
+) -> Result<HashMap<nr::ID, D>>
 where
 	R: DependencyResolver<Dependency = D>
 {
-	let deps = deps
-		.into_inner()
-		.unwrap_or_default();
-
 	let mut satisfied = HashMap::with_capacity(deps.len());
 	let mut not_satisfied = Vec::with_capacity(deps.len());
 
 	for (id, req) in deps {
-		let id = nr::ID::new(id.into_inner());
-		let req = ::semver::VersionReq::parse(req.ref_inner())?;
-
 		let dep = {
 			use DependencyResult::*;
-			match dep_resolver.dependency(&id, &req).await? {
+			match dep_resolver.dependency(id, req).await? {
 				Found(d) => { d }
+				NotFound if optional => { continue }
 				NotFound => {
-					not_satisfied.push((id, req, None));
+					not_satisfied.push((id.clone(), req.clone(), None));
 					continue
 				}
 				VersionNotSatisfied(v) => {
-					not_satisfied.push((id, req, Some(v)));
+					not_satisfied.push((id.clone(), req.clone(), Some(v)));
 					continue
 				}
 			}
 		};
 
-		satisfied.insert(id, (dep, req));
+		satisfied.insert(id.clone(), dep);
 	}
 
 	if !not_satisfied.is_empty() {
@@ -187,54 +483,141 @@ where
 	Ok(satisfied)
 }
 
+/// Errors if any pack this pack is incompatible with is loaded, with a
+/// version matching the requirement (or any version, if it doesn't have one)
+async fn check_incompatible<R, D>(
+	incompatible_with: &BTreeMap<nr::ID, ::semver::VersionReq>,
+	dep_resolver: &R
+) -> Result<()>
+where
+	R: DependencyResolver<Dependency = D>,
+	D: Dependency
+{
+	let mut incompatible = Vec::new();
+
+	for (id, req) in incompatible_with {
+		if let DependencyResult::Found(d) = dep_resolver.dependency(id, req).await? {
+			incompatible.push((id.clone(), req.clone(), d.version().cloned()));
+		}
+	}
+
+	if !incompatible.is_empty() {
+		return Err(Error::Incompatible(incompatible))
+	}
+
+	Ok(())
+}
+
+fn check_extends<'h, I, D>(textures: I, dependencies: &HashMap<nr::ID, D>) -> Result<()>
+where
+	I: IntoIterator<Item = &'h TextureRuntime>,
+	D: Dependency
+{
+	for texture in textures {
+		let extends = match texture.extends().ref_inner() {
+			Some(e) => { e }
+			None => { continue }
+		};
+
+		let texture_id = texture.id().ref_inner().clone();
+		let extends_pack_id = extends.pack_id().ref_inner().clone();
+		let extends_texture_id = extends.texture_id().ref_inner().clone();
+
+		let dependency = dependencies.get(extends.pack_id())
+			.ok_or_else(|| Error::ExtendsNonDependency {
+				texture_id: texture_id.clone(),
+				pack_id: extends_pack_id.clone()
+			})?;
+
+		let extended = dependency.texture(extends.texture_id())
+			.ok_or_else(|| Error::ExtendsUnknownTexture {
+				texture_id: texture_id.clone(),
+				pack_id: extends_pack_id.clone(),
+				extends_texture_id: extends_texture_id.clone()
+			})?;
+
+		if extended.extends().ref_inner().is_some() {
+			return Err(Error::ExtendsExtendingTexture {
+				texture_id,
+				pack_id: extends_pack_id,
+				extends_texture_id
+			})
+		}
+	}
+
+	Ok(())
+}
+
 #[inline]
-async fn read_textures(p: &WithRootDir<'_>) -> Result<nr::Textures> {
-	let texture_entries_dir = p.texture_entries_dir_checked().await?;
+async fn read_textures(p: &WithRootDir<'_>, ctx: &mut LoadContext) -> nr::Textures {
 	let mut textures_nom = nr::Textures::default();
 	let textures = textures_nom.mut_inner();
 
-	let mut read_dir = fs::read_dir2(texture_entries_dir).await?;
-
-	while let Some(file) = read_dir.next().await? {
-		let file_name = file.file_name();
-		let p = p.clone().with_texture_id_osstr(&file_name)?;
-
-		// TODO
-		if let Some(t) = TextureRuntime::new(&p).await? {
-			let id = texture2::nr::ID::new(p.texture_id_ref().into());
-			textures.insert(id, t);
+	let texture_ids = match p.texture_entries_dir_checked().await {
+		Ok(dir) => { read_entry_names(ctx, dir).await }
+		Err(e) => {
+			ctx.error(p.texture_entries_dir_unchecked(), e);
+			Vec::new()
+		}
+	};
+	let results = load_concurrently(ctx, &texture_ids, |texture_id, mut ctx| async move {
+		let p = p.clone().with_texture_id(texture_id);
+		let result = TextureRuntime::new(&p, &mut ctx).await;
+		((p, result), ctx)
+	}).await;
+
+	for ((p, result), texture_ctx) in results {
+		ctx.merge(texture_ctx);
+		match result {
+			Ok(Some(t)) => {
+				let id = texture2::nr::ID::new(p.texture_id_ref().into());
+				textures.insert(id, t);
+			}
+			Ok(None) => {}
+			Err(e) => { ctx.error(p.texture_dir_unchecked(), e); }
 		}
 	}
 
-	Ok(textures_nom)
+	textures_nom
 }
 
 #[derive(Serialize)]
 pub struct FrontendData<'h> {
-	name: &'h nr::Name,
-	description: &'h nr::Description,
+	name: &'h str,
+	description: Option<&'h str>,
 	id: &'h nr::ID,
 	dir: &'h nr::Dir,
 	version: &'h nr::Version,
 	dependencies: &'h nr::Dependencies,
-	textures: HashMap<&'h str, texture2::FrontendData<'h>>
+	optional_dependencies: &'h nr::OptionalDependencies,
+	incompatible_with: &'h nr::IncompatibleWith,
+	textures: BTreeMap<&'h str, texture2::FrontendData<'h>>,
+	diagnostics: &'h nr::Diagnostics
 }
 
 impl<'h> FrontendData<'h> {
-	pub fn new(pack: &'h PackRuntime, mc_version: MCVersionRef) -> Self {
-		let name = &pack.name;
-		let description = &pack.description;
+	/// `merge` should merge textures extending the provided texture into it
+	/// (see [`MergedTexture`])
+	pub fn new<F>(pack: &'h PackRuntime, mc_version: MCVersionRef, locales: &[String], merge: F) -> Self
+	where
+		F: Fn(&'h TextureRuntime) -> MergedTexture<'h>
+	{
+		let name = pack.name.ref_inner().get(locales);
+		let description = pack.description.ref_inner().as_ref().map(|d| d.get(locales));
 		let id = &pack.id;
 		let dir = &pack.dir;
 		let version = &pack.version;
 		let dependencies = &pack.dependencies;
+		let optional_dependencies = &pack.optional_dependencies;
+		let incompatible_with = &pack.incompatible_with;
 		let textures = pack.textures.ref_inner()
 			.iter()
 			.map(|(id, t)| (
 				&**id.ref_inner(),
-				texture2::FrontendData::new(t, mc_version)
+				texture2::FrontendData::new(&merge(t), mc_version, locales)
 			))
 			.collect();
+		let diagnostics = &pack.diagnostics;
 
 		Self {
 			name,
@@ -243,7 +626,10 @@ impl<'h> FrontendData<'h> {
 			dir,
 			version,
 			dependencies,
-			textures
+			optional_dependencies,
+			incompatible_with,
+			textures,
+			diagnostics
 		}
 	}
 }
@@ -5,6 +5,21 @@ pub type Result<T, E = Error> = StdResult<T, E>;
 
 #[derive(Debug, Error)]
 pub enum Error {
+	#[error("provider versions list is empty, so it would never be available")]
+	EmptyVersions,
+
+	#[error("error writing manifest:\n{}", ::ts_result::NiceErrorMessage::to_error_message(.0))]
+	SerialiseError(crate::error::ron_err::Ron),
+
+	#[error("unknown minecraft version {0} in provider versions")]
+	UnknownMCVersion(String),
+
+	#[error("unknown minecraft version in provider versions range {0} to {1}")]
+	UnknownMCVersionRange(String, String),
+
+	#[error("no known minecraft version has pack format {0}, in provider versions")]
+	UnknownPackFormat(u8),
+
 	#[error(transparent)]
 	UtilError(#[from] crate::util::error::Error)
 }
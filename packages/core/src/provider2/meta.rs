@@ -1,27 +1,98 @@
 use crate::gen::Generator;
-use crate::util::ron;
+use crate::util::{ ron, ron2 };
 use super::error::*;
+use super::nm;
+use ::schemars::{ schema::RootSchema, schema_for, JsonSchema };
 use ::serde::{ Deserialize, Serialize };
 
-#[derive(Serialize, Deserialize)]
+/// Manifest of a provider (`provider.wiwimeta`)
+#[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "meta_version")]
 enum ProviderMeta {
 	#[serde(rename = "1")]
 	Version1 {
 		#[serde(flatten)]
 		gen: Generator
+	},
+	#[serde(rename = "2")]
+	Version2 {
+		versions: nm::Versions,
+		#[serde(flatten)]
+		gen: Generator
+	},
+	/// same as version 2, but `versions` is optional, so version 1 manifests
+	/// (available for everything the generator supports) can be upgraded
+	#[serde(rename = "3")]
+	Version3 {
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		versions: Option<nm::Versions>,
+		#[serde(flatten)]
+		gen: Generator
 	}
 }
 
+/// Untagged, since manifests are buffered (for `meta_version` and the
+/// flattened generator), and ron can't deserialise externally tagged enums
+/// from that. So in a manifest, a pack format is just a number (`22`), a
+/// minecraft version is a string (`"1.20.4"`), and an inclusive range of
+/// minecraft versions is a tuple (`("1.13", "1.16.5")`).
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(untagged)]
+pub enum PackVersionSpecMeta {
+	PackVersion(u8),
+	MCVersion(String),
+	MCVersionRange(String, String)
+}
+
 pub(super) struct ProviderUnversioned {
+	/// `None` for manifests from before `versions` existed, which are
+	/// available for any version the generator supports
+	pub(super) versions: Option<nm::Versions>,
 	pub(super) gen: Generator
 }
 
 pub(super) fn deserialise_version(s: &str) -> Result<ProviderUnversioned> {
+	Ok(unversion(ron::from_str(s)?))
+}
+
+/// Writes a manifest, using the latest `meta_version`
+pub(super) fn serialise_provider(provider: ProviderUnversioned) -> Result<String> {
+	let ProviderUnversioned { versions, gen } = provider;
+	let provider = ProviderMeta::Version3 { versions, gen };
+	ron2::to_string_pretty(&provider).map_err(Error::SerialiseError)
+}
+
+/// Manifest for a new provider. Without `versions`, it's available for any
+/// version the generator supports
+pub(crate) fn new_provider(versions: Option<Vec<PackVersionSpecMeta>>, gen: Generator) -> Result<String> {
+	let versions = versions.map(nm::Versions::new);
+	serialise_provider(ProviderUnversioned { versions, gen })
+}
+
+/// Upgrades a manifest to the latest `meta_version`, or `None` if it
+/// already is the latest
+pub(crate) fn upgrade_provider(s: &str) -> Result<Option<String>> {
+	let meta = ron::from_str(s)?;
+	if let ProviderMeta::Version3 { .. } = meta { return Ok(None) }
+	serialise_provider(unversion(meta)).map(Some)
+}
+
+/// JSON schema of the manifest, covering every `meta_version`
+pub(crate) fn provider_schema() -> RootSchema {
+	schema_for!(ProviderMeta)
+}
+
+fn unversion(meta: ProviderMeta) -> ProviderUnversioned {
 	use ProviderMeta::*;
-	Ok(match ron::from_str(s)? {
+	match meta {
 		Version1 { gen } => {
-			ProviderUnversioned { gen }
+			ProviderUnversioned { versions: None, gen }
+		}
+		Version2 { versions, gen } => {
+			ProviderUnversioned { versions: Some(versions), gen }
 		}
-	})
+		Version3 { versions, gen } => {
+			ProviderUnversioned { versions, gen }
+		}
+	}
 }
@@ -5,10 +5,15 @@ mod meta;
 mod rt;
 
 pub use self::error::Error;
+pub(crate) use self::meta::{ new_provider, provider_schema, upgrade_provider, PackVersionSpecMeta };
 pub use self::rt::{ PackVersionSpecRuntime, ProviderRuntime, FrontendData };
 
 ::nominal::nominal_mod! {
+	mod nm {
+		nominal!(pub Versions, inner: Vec<super::meta::PackVersionSpecMeta>);
+	}
 	pub mod nr {
 		nominal!(pub ID, inner: String);
+		nominal!(pub Versions, inner: Option<Vec<super::PackVersionSpecRuntime>>);
 	}
 }
@@ -1,13 +1,17 @@
+use crate::build::Files;
+use crate::error::build_err;
 use crate::gen::Generator;
+use crate::load::LoadContext;
 use ::mc_versions::{ MC_VERSIONS, MCVersionRef, MCVersionRefSlice };
 use crate::util::fs;
 use crate::util::path_builder3::WithProviderID;
 use super::error::*;
-use super::{ meta, nr };
+use super::{ meta, nm, nr };
 use ::serde::Serialize;
 
 pub struct ProviderRuntime {
 	id: nr::ID,
+	versions: nr::Versions,
 	gen: Generator
 }
 
@@ -18,6 +22,30 @@ pub enum PackVersionSpecRuntime {
 }
 
 impl PackVersionSpecRuntime {
+	fn new(spec: meta::PackVersionSpecMeta) -> Result<Self> {
+		use meta::PackVersionSpecMeta::*;
+		Ok(match spec {
+			PackVersion(pack_format) => {
+				let exists = MC_VERSIONS.iter()
+					.any(|v| v.pack_format.get_version() == Some(pack_format));
+				if !exists { return Err(Error::UnknownPackFormat(pack_format)) }
+				Self::PackVersion(pack_format)
+			}
+
+			MCVersion(version) => {
+				let version = ::mc_versions::MCVersion::get(&version)
+					.map_err(|_| Error::UnknownMCVersion(version))?;
+				Self::MCVersion(version)
+			}
+
+			MCVersionRange(from, to) => {
+				let range = ::mc_versions::MCVersion::get_range(&from, &to)
+					.map_err(|_| Error::UnknownMCVersionRange(from, to))?;
+				Self::MCVersionRange(range)
+			}
+		})
+	}
+
 	pub fn satisfies(&self, mc_version: MCVersionRef) -> bool {
 		use PackVersionSpecRuntime::*;
 		match self {
@@ -40,21 +68,72 @@ impl PackVersionSpecRuntime {
 }
 
 impl ProviderRuntime {
-	pub(crate) async fn new(p: &WithProviderID<'_>) -> Result<Option<Self>> {
+	pub(crate) async fn new(p: &WithProviderID<'_>, ctx: &mut LoadContext) -> Result<Option<Self>> {
 		let dir = p.provider_dir_silent_fail().await?;
 		let meta_path = p.provider_manifest_silent_fail().await?;
 		let meta_file = fs::read_to_string2(meta_path).await?;
 		let meta::ProviderUnversioned {
-			gen
+			versions,
+			mut gen
 		} = meta::deserialise_version(&meta_file)?;
+		gen.load(&dir, ctx).await?;
 
 		let id = nr::ID::new(p.provider_id_ref().into());
+		let versions = read_versions(versions)?;
 
 		Ok(Some(Self {
 			id,
+			versions,
 			gen
 		}))
 	}
+
+	#[inline]
+	pub fn id(&self) -> &nr::ID {
+		&self.id
+	}
+
+	#[inline]
+	pub fn versions(&self) -> &nr::Versions {
+		&self.versions
+	}
+
+	#[inline]
+	pub(crate) fn gen(&self) -> &Generator {
+		&self.gen
+	}
+
+	/// A provider is available if the generator supports the version, and
+	/// the version is in the provider's `versions` list (if it has one)
+	pub fn is_available_for(&self, mc_version: MCVersionRef) -> bool {
+		let in_versions = match self.versions.ref_inner() {
+			Some(versions) => { versions.iter().any(|v| v.satisfies(mc_version)) }
+			None => { true }
+		};
+
+		in_versions && self.gen.is_available_for(mc_version)
+	}
+
+	#[inline]
+	pub async fn generate(&self, mc_version: MCVersionRef, files: &mut Files)
+		-> Result<(), build_err::AddFile>
+	{
+		self.gen.generate(mc_version, files).await
+	}
+}
+
+fn read_versions(versions: Option<nm::Versions>) -> Result<nr::Versions> {
+	let versions = match versions {
+		Some(versions) => { versions.into_inner() }
+		None => { return Ok(nr::Versions::new(None)) }
+	};
+
+	if versions.is_empty() { return Err(Error::EmptyVersions) }
+
+	let versions = versions.into_iter()
+		.map(PackVersionSpecRuntime::new)
+		.collect::<Result<_>>()?;
+	Ok(nr::Versions::new(Some(versions)))
 }
 
 #[derive(Serialize)]
@@ -64,12 +143,7 @@ pub struct FrontendData<'h> {
 
 impl<'h> FrontendData<'h> {
 	pub fn new(provider: &'h ProviderRuntime, mc_version: MCVersionRef) -> Option<Self> {
-		let res = match &provider.gen {
-			Generator::RandomCubeAll { gen } => { gen.is_available_for(mc_version) }
-			Generator::RandomLeaves { gen } => { gen.is_available_for(mc_version) }
-		};
-
-		if res {
+		if provider.is_available_for(mc_version) {
 			let id = &provider.id;
 			Some(Self { id })
 		} else {
@@ -77,3 +151,39 @@ impl<'h> FrontendData<'h> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::meta::PackVersionSpecMeta::*;
+	use crate::test_util::mc_version;
+
+	fn versions(specs: Vec<meta::PackVersionSpecMeta>) -> Result<nr::Versions> {
+		read_versions(Some(nm::Versions::new(specs)))
+	}
+
+	#[test]
+	fn versions_validated() {
+		assert!(versions(vec![]).is_err());
+		assert!(versions(vec![PackVersion(255)]).is_err());
+		assert!(versions(vec![MCVersion("1.99.99".into())]).is_err());
+		assert!(versions(vec![MCVersionRange("1.13".into(), "1.99.99".into())]).is_err());
+		assert!(read_versions(None).unwrap().ref_inner().is_none());
+	}
+
+	#[test]
+	fn versions_satisfied() {
+		let versions = versions(vec![
+			PackVersion(3),
+			MCVersionRange("1.20".into(), "1.19".into())
+		]).unwrap();
+		let versions = versions.ref_inner().as_ref().unwrap();
+		let satisfies = |v| versions.iter().any(|s| s.satisfies(mc_version(v)));
+
+		assert!(satisfies("1.12.2"));
+		assert!(satisfies("1.19.4"));
+		assert!(satisfies("1.20"));
+		assert!(!satisfies("1.13"));
+		assert!(!satisfies("1.20.4"));
+	}
+}
@@ -0,0 +1,196 @@
+//! Creating new packs, textures, options and providers on disk, with manifests
+//! to start editing from
+
+use crate::{ option2, pack2, provider2, texture2 };
+use crate::error::common::ManifestError;
+use crate::error::scaffold_err::{ self, Scaffold };
+use crate::gen::{ CopyFiles, Generator, COPY_FILES_DEFAULT_DIR };
+use crate::util::create_path_builder3;
+use crate::util::fs2;
+
+/// Creates a new pack in `dir`, with an empty `textures` dir. `dir` doesn't
+/// have to be empty (ex. it can be an existing git repo), but it can't have a
+/// pack in it already.
+pub async fn create_pack(dir: &str, id: &str, name: &str) -> Result<(), Scaffold> {
+	check_id("pack", id)?;
+	let p = create_path_builder3().with_root_dir(dir);
+
+	let manifest_path = p.root_manifest_unchecked();
+	let manifest = manifest_contents(&manifest_path, pack2::new_pack(id, name, None))?;
+
+	fs2::create_dir_all(|| dir.into()).await?;
+	write_manifest(manifest_path, manifest).await?;
+	fs2::create_dir_all(|| p.texture_entries_dir_unchecked()).await?;
+	Ok(())
+}
+
+/// Creates a new texture, with no options, in the pack in `pack_dir`
+pub async fn create_texture(pack_dir: &str, texture_id: &str, name: &str) -> Result<(), Scaffold> {
+	check_id("texture", texture_id)?;
+	let p = create_path_builder3().with_root_dir(pack_dir);
+	require_parent("pack", p.root_manifest_unchecked()).await?;
+
+	let p = p.with_texture_id(texture_id);
+	let manifest = texture2::new_texture(name, None);
+	create_entry(p.texture_dir_unchecked(), p.texture_manifest_unchecked(), manifest).await
+}
+
+/// Creates a new option, with no providers, in a texture in the pack in
+/// `pack_dir`. It isn't made the texture's default.
+pub async fn create_option(
+	pack_dir: &str,
+	texture_id: &str,
+	option_id: &str,
+	name: &str
+) -> Result<(), Scaffold> {
+	check_id("texture", texture_id)?;
+	check_id("option", option_id)?;
+	let p = create_path_builder3()
+		.with_root_dir(pack_dir)
+		.with_texture_id(texture_id);
+	require_parent("texture", p.texture_manifest_unchecked()).await?;
+
+	let p = p.with_option_id(option_id);
+	let manifest = option2::new_option(name);
+	create_entry(p.option_dir_unchecked(), p.option_manifest_unchecked(), manifest).await
+}
+
+/// Creates a new provider in an option in the pack in `pack_dir`, that copies
+/// the files put in its (new, empty) `files` dir into the pack, for any
+/// minecraft version
+pub async fn create_provider(
+	pack_dir: &str,
+	texture_id: &str,
+	option_id: &str,
+	provider_id: &str
+) -> Result<(), Scaffold> {
+	check_id("texture", texture_id)?;
+	check_id("option", option_id)?;
+	check_id("provider", provider_id)?;
+	let p = create_path_builder3()
+		.with_root_dir(pack_dir)
+		.with_texture_id(texture_id)
+		.with_option_id(option_id);
+	require_parent("option", p.option_manifest_unchecked()).await?;
+
+	let p = p.with_provider_id(provider_id);
+	let dir = p.provider_dir_unchecked();
+	let gen = Generator::CopyFiles { gen: CopyFiles::default() };
+	let manifest = provider2::new_provider(None, gen);
+	create_entry(dir.clone(), p.provider_manifest_unchecked(), manifest).await?;
+
+	fs2::create_dir_all(|| format!("{dir}/{COPY_FILES_DEFAULT_DIR}")).await?;
+	Ok(())
+}
+
+/// IDs are used as dir names, and in option IDs, so they follow the same rules
+pub(crate) fn check_id(kind: &'static str, id: &str) -> Result<(), Scaffold> {
+	let invalid_chars = ::wiwipaccer_common::mc_id::invalid_chars(id);
+	match invalid_chars {
+		Some(invalid_chars) => { Err(scaffold_err::invalid_id(kind, id.into(), invalid_chars)) }
+		None if id.is_empty() => { Err(scaffold_err::invalid_id(kind, id.into(), Vec::new())) }
+		None => { Ok(()) }
+	}
+}
+
+pub(crate) async fn refuse_existing(path: &str) -> Result<(), Scaffold> {
+	match fs2::exists(|| path.into()).await? {
+		true => { Err(scaffold_err::already_exists(path.into())) }
+		false => { Ok(()) }
+	}
+}
+
+async fn require_parent(kind: &'static str, manifest: String) -> Result<(), Scaffold> {
+	match fs2::exists(|| manifest.clone()).await? {
+		true => { Ok(()) }
+		false => { Err(scaffold_err::parent_not_found(kind, manifest)) }
+	}
+}
+
+/// Creates `dir` and writes `manifest` into it, if `dir` doesn't exist already
+async fn create_entry<E: Into<ManifestError>>(
+	dir: String,
+	manifest_path: String,
+	manifest: Result<String, E>
+) -> Result<(), Scaffold> {
+	refuse_existing(&dir).await?;
+	let manifest = manifest_contents(&manifest_path, manifest)?;
+
+	fs2::create_dir_all(|| dir.clone()).await?;
+	write_manifest(manifest_path, manifest).await
+}
+
+/// Writes a new manifest, refusing to overwrite one that's there already
+async fn write_manifest(path: String, manifest: Vec<u8>) -> Result<(), Scaffold> {
+	match fs2::write_new(|| path.clone(), manifest).await? {
+		true => { Ok(()) }
+		false => { Err(scaffold_err::already_exists(path)) }
+	}
+}
+
+pub(crate) fn manifest_contents<E: Into<ManifestError>>(path: &str, manifest: Result<String, E>)
+	-> Result<Vec<u8>, Scaffold>
+{
+	let mut manifest = manifest.map_err(|e| scaffold_err::manifest(path.into(), e))?;
+	manifest.push('\n');
+	Ok(manifest.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::load::Strictness;
+	use crate::test_util;
+	use crate::validate;
+	use crate::workspace2::{ self, WorkspaceRuntime };
+
+	#[tokio::test]
+	async fn scaffolded_pack_loads() {
+		let scratch = test_util::scratch_dir("scaffold");
+		let dir = scratch.to_str().unwrap();
+
+		create_pack(dir, "new", "New").await.unwrap();
+		create_texture(dir, "stone", "Stone").await.unwrap();
+		create_option(dir, "stone", "smooth", "Smooth").await.unwrap();
+		create_provider(dir, "stone", "smooth", "all").await.unwrap();
+		::std::fs::write(scratch.join("textures/stone/smooth/all/files/stone.png"), "").unwrap();
+
+		let mut workspace = WorkspaceRuntime::new(workspace2::nr::Name::new("uwu".into()));
+		workspace.set_strictness(Strictness::Strict);
+		workspace.add_pack(dir).await.unwrap();
+
+		let pack = workspace.packs().next().unwrap();
+		assert_eq!("new", pack.id().ref_inner());
+		assert!(validate::validate_pack(pack).await.unwrap().is_empty());
+
+		let stone = pack.textures().ref_inner().values().next().unwrap();
+		assert_eq!("Stone", stone.name().ref_inner().get::<&str>(&[]));
+		assert_eq!(1, stone.options().ref_inner().len());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn refuses_to_overwrite() {
+		let scratch = test_util::scratch_copy("basic", "scaffold-overwrite");
+		let dir = scratch.to_str().unwrap();
+		let pack_manifest = ::std::fs::read_to_string(scratch.join("pack.wiwimeta")).unwrap();
+
+		assert!(matches!(create_pack(dir, "basic", "Basic").await, Err(Scaffold::AlreadyExists { .. })));
+		assert!(matches!(create_texture(dir, "stone", "Stone").await, Err(Scaffold::AlreadyExists { .. })));
+		assert!(matches!(create_option(dir, "stone", "plain", "Plain").await, Err(Scaffold::AlreadyExists { .. })));
+		assert!(matches!(create_provider(dir, "stone", "plain", "all").await, Err(Scaffold::AlreadyExists { .. })));
+
+		assert!(matches!(create_option(dir, "nope", "plain", "Plain").await, Err(Scaffold::ParentNotFound { .. })));
+		assert!(matches!(create_texture(dir, "Stone!", "Stone").await, Err(Scaffold::InvalidID { .. })));
+		assert!(matches!(create_texture(dir, "", "Stone").await, Err(Scaffold::InvalidID { .. })));
+		assert!(matches!(create_option(dir, "../stone", "new", "New").await, Err(Scaffold::InvalidID { .. })));
+		assert!(matches!(create_provider(dir, "stone", "..", "new").await, Err(Scaffold::InvalidID { .. })));
+
+		// nothing was changed
+		assert_eq!(pack_manifest, ::std::fs::read_to_string(scratch.join("pack.wiwimeta")).unwrap());
+		assert!(!scratch.join("textures/stone/plain/all/files").exists());
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+}
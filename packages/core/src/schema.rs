@@ -0,0 +1,144 @@
+//! JSON schemas for the manifests in a pack source, generated from the same
+//! types the manifests are deserialised into, so they can't go out of date.
+//!
+//! Manifests are ron, not JSON, but the data model maps over (structs and
+//! maps are objects, tuples and lists are arrays, `None` is null), so editors
+//! that can use JSON schemas for ron files can validate and autocomplete them.
+
+use crate::{ option2, pack2, provider2, texture2 };
+use crate::util::path_builder3::{ OPTION_MANIFEST, PROVIDER_MANIFEST, ROOT_MANIFEST, TEXTURE_MANIFEST };
+use ::schemars::schema::RootSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Manifest {
+	Pack,
+	Texture,
+	Option,
+	Provider
+}
+
+impl Manifest {
+	pub const ALL: [Self; 4] = [Self::Pack, Self::Texture, Self::Option, Self::Provider];
+
+	/// File name of this kind of manifest (ex. `pack.wiwimeta`)
+	pub fn file_name(self) -> &'static str {
+		use Manifest::*;
+		match self {
+			Pack => { ROOT_MANIFEST }
+			Texture => { TEXTURE_MANIFEST }
+			Option => { OPTION_MANIFEST }
+			Provider => { PROVIDER_MANIFEST }
+		}
+	}
+
+	/// Kind of manifest with the provided file name, if any
+	pub fn from_file_name(file_name: &str) -> ::std::option::Option<Self> {
+		Self::ALL.into_iter().find(|m| m.file_name() == file_name)
+	}
+
+	/// Schema of this kind of manifest, accepting every `meta_version` that
+	/// can still be loaded
+	pub fn schema(self) -> RootSchema {
+		use Manifest::*;
+		let mut schema = match self {
+			Pack => { pack2::pack_schema() }
+			Texture => { texture2::texture_schema() }
+			Option => { option2::option_schema() }
+			Provider => { provider2::provider_schema() }
+		};
+		schema.schema.metadata().title = Some(self.file_name().into());
+		schema
+	}
+
+	/// [`schema`](Self::schema), as pretty printed JSON
+	pub fn schema_json(self) -> String {
+		::serde_json::to_string_pretty(&self.schema())
+			.expect("schemas only have string keys")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_util;
+	use crate::util::ron;
+	use ::jsonschema::JSONSchema;
+	use ::serde_json::{ json, Value };
+	use ::std::path::Path;
+
+	fn manifests(dir: &Path, found: &mut Vec<(Manifest, String, String)>) {
+		for entry in ::std::fs::read_dir(dir).unwrap() {
+			let path = entry.unwrap().path();
+			if path.is_dir() { manifests(&path, found); continue }
+
+			let file_name = path.file_name().unwrap().to_str().unwrap();
+			if let Some(manifest) = Manifest::from_file_name(file_name) {
+				let contents = ::std::fs::read_to_string(&path).unwrap();
+				found.push((manifest, path.to_str().unwrap().into(), contents));
+			}
+		}
+	}
+
+	/// A manifest as JSON, the way an editor validating it would see it
+	fn to_json(contents: &str) -> Value {
+		let value = ron::from_str::<::ron::Value>(contents).unwrap();
+		::serde_json::to_value(value).unwrap()
+	}
+
+	fn compile(manifest: Manifest) -> JSONSchema {
+		let schema = ::serde_json::to_value(manifest.schema()).unwrap();
+		assert_eq!(Some(manifest.file_name()), schema["title"].as_str());
+		JSONSchema::compile(&schema).unwrap()
+	}
+
+	#[test]
+	fn provider_schema_has_every_generator() {
+		let schema = Manifest::Provider.schema_json();
+		for gen in ["random-cube-all", "random-leaves", "copy-files"] {
+			assert!(schema.contains(&format!("\"{gen}\"")), "{gen} missing from schema");
+		}
+	}
+
+	#[test]
+	fn fixture_manifests_match_schema() {
+		let mut found = Vec::new();
+		manifests(test_util::fixtures_dir().as_ref(), &mut found);
+		// broken on purpose
+		found.retain(|(_, path, _)| !path.contains("/broken-"));
+
+		for manifest in Manifest::ALL {
+			let schema = compile(manifest);
+			let mut count = 0;
+
+			for (_, path, contents) in found.iter().filter(|(m, ..)| *m == manifest) {
+				let value = to_json(contents);
+				if let Err(errors) = schema.validate(&value) {
+					let errors = errors.map(|e| e.to_string()).collect::<Vec<_>>();
+					panic!("{path} doesn't match its schema:\n{}", errors.join("\n"));
+				}
+				count += 1;
+			}
+
+			assert!(count > 0, "no fixtures for {}", manifest.file_name());
+		}
+	}
+
+	#[test]
+	fn invalid_manifests_dont_match_schema() {
+		let schema = compile(Manifest::Pack);
+		assert!(schema.is_valid(&json!({ "meta_version": "2", "name": "uwu", "id": "uwu" })));
+		// missing id
+		assert!(!schema.is_valid(&json!({ "meta_version": "2", "name": "uwu" })));
+		// unknown version
+		assert!(!schema.is_valid(&json!({ "meta_version": "0", "name": "uwu", "id": "uwu" })));
+		assert!(!schema.is_valid(&json!({ "meta_version": "2", "name": 5, "id": "uwu" })));
+		// translations only from version 3 on
+		assert!(!schema.is_valid(&json!({ "meta_version": "2", "name": { "en": "uwu" }, "id": "uwu" })));
+		assert!(schema.is_valid(&json!({ "meta_version": "3", "name": { "en": "uwu" }, "id": "uwu" })));
+
+		// missing name
+		let schema = compile(Manifest::Texture);
+		let path = format!("{}/textures/dirt/texture.wiwimeta", test_util::fixture("broken-many"));
+		assert!(!schema.is_valid(&to_json(&::std::fs::read_to_string(path).unwrap())));
+	}
+}
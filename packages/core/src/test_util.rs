@@ -0,0 +1,73 @@
+//! helpers shared by tests, mostly for using the packs in `test/fixtures/packs`
+
+use crate::build::Files;
+use crate::error::ron_err;
+use crate::gen::Generator;
+use crate::util::ron2 as ron;
+use ::mc_versions::{ MCVersion, MCVersionRef };
+use ::std::fs;
+use ::std::path::{ Path, PathBuf };
+
+/// The dir all the fixture packs are in
+pub(crate) fn fixtures_dir() -> String {
+	format!("{}/test/fixtures/packs", env!("CARGO_MANIFEST_DIR"))
+}
+
+pub(crate) fn fixture(pack: &str) -> String {
+	format!("{}/{pack}", fixtures_dir())
+}
+
+pub(crate) fn mc_version(name: &str) -> MCVersionRef {
+	MCVersion::get(name).map_err(|_| ()).unwrap()
+}
+
+/// Parses a generator written without its tag (ex. `(block_id: "stone")`) as
+/// a `gen_type` generator. This goes through [`Generator`] like provider
+/// manifests do, since flattened fields need the tag to be parsed as a struct
+pub(crate) fn parse_gen(gen_type: &str, gen: &str) -> Result<Generator, ron_err::Ron> {
+	let gen = format!(r#"(type: "{gen_type}", {})"#, &gen[1..gen.len() - 1]);
+	ron::from_str(&gen)
+}
+
+/// Generates the files of a block generator (see [`parse_gen`])
+pub(crate) fn generate(gen_type: &str, gen: &str, mc_version: &str) -> Files {
+	let gen = parse_gen(gen_type, gen).unwrap();
+	let mut files = Files::new();
+	// the block generators don't do any IO, so there's nothing to wait on
+	::futures::executor::block_on(gen.generate(self::mc_version(mc_version), &mut files)).unwrap();
+	files
+}
+
+pub(crate) fn json(files: &Files, path: &str) -> ::serde_json::Value {
+	::serde_json::from_slice(files.get(path).unwrap()).unwrap()
+}
+
+/// A fresh, empty directory in the system temp dir, for a test to write into.
+/// `name` should be unique to the test.
+pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+	let scratch = ::std::env::temp_dir()
+		.join(format!("wiwipaccer-test-{name}-{}", ::std::process::id()));
+	let _ = fs::remove_dir_all(&scratch);
+	fs::create_dir_all(&scratch).unwrap();
+	scratch
+}
+
+/// Copies a fixture pack into a [`scratch_dir`], so a test can change it.
+/// `name` should be unique to the test.
+pub(crate) fn scratch_copy(pack: &str, name: &str) -> PathBuf {
+	let scratch = scratch_dir(name);
+	copy_dir(fixture(pack).as_ref(), &scratch);
+	scratch
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+	fs::create_dir_all(to).unwrap();
+	for entry in fs::read_dir(from).unwrap() {
+		let entry = entry.unwrap();
+		let to = to.join(entry.file_name());
+		match entry.file_type().unwrap().is_dir() {
+			true => { copy_dir(&entry.path(), &to) }
+			false => { fs::copy(entry.path(), to).map(|_| ()).unwrap() }
+		}
+	}
+}
@@ -5,12 +5,27 @@ pub type Result<T, E = Error> = StdResult<T, E>;
 
 #[derive(Debug, Error)]
 pub enum Error {
+	#[error("default option \"{default}\" of texture \"{texture_id}\" does not exist")]
+	DefaultNotFound {
+		texture_id: String,
+		default: String
+	},
+
 	#[error("non UTF-8 paths are not supported")]
 	NonUtf8Path,
 
+	#[error("texture \"{texture_id}\" extends \"{extends}\", which isn't in the form pack_id:texture_id")]
+	InvalidExtends {
+		texture_id: String,
+		extends: String
+	},
+
 	#[error(transparent)]
 	OptionError(#[from] crate::option2::Error),
 
+	#[error("error writing manifest:\n{}", ::ts_result::NiceErrorMessage::to_error_message(.0))]
+	SerialiseError(crate::error::ron_err::Ron),
+
 	#[error(transparent)]
 	UtilError(#[from] crate::util::error::Error)
 }
@@ -1,30 +1,94 @@
-use crate::util::ron;
+use crate::locale::{ localise_plain, Localised };
+use crate::util::{ ron, ron2 };
 use super::error::*;
 use super::nm;
+use ::schemars::{ schema::RootSchema, schema_for, JsonSchema };
 use ::serde::{ Deserialize, Serialize };
 
-#[derive(Deserialize, Serialize)]
+/// Manifest of a texture (`texture.wiwimeta`)
+#[derive(Deserialize, JsonSchema, Serialize)]
 #[serde(tag = "meta_version")]
 enum TextureMeta {
 	#[serde(rename = "1")]
 	Version1 {
+		name: nm::PlainName,
+		description: Option<nm::PlainDescription>,
+		default: Option<nm::Default>
+	},
+	#[serde(rename = "2")]
+	Version2 {
+		name: nm::PlainName,
+		description: Option<nm::PlainDescription>,
+		default: Option<nm::Default>,
+		#[serde(default)]
+		extends: Option<nm::Extends>
+	},
+	/// same as version 2, but `name` and `description` can be translated
+	#[serde(rename = "3")]
+	Version3 {
 		name: nm::Name,
-		description: nm::Description,
-		default: nm::Default
+		description: Option<nm::Description>,
+		default: Option<nm::Default>,
+		#[serde(default)]
+		extends: Option<nm::Extends>
 	}
 }
 
 pub(super) struct TextureUnversioned {
 	pub(super) name: nm::Name,
-	pub(super) description: nm::Description,
-	pub(super) default: nm::Default
+	pub(super) description: Option<nm::Description>,
+	pub(super) default: Option<nm::Default>,
+	pub(super) extends: Option<nm::Extends>
 }
 
 pub(super) fn deserialise_texture(s: &str) -> Result<TextureUnversioned> {
+	Ok(unversion(ron::from_str(s)?))
+}
+
+/// Writes a manifest, using the latest `meta_version`
+pub(super) fn serialise_texture(texture: TextureUnversioned) -> Result<String> {
+	let TextureUnversioned { name, description, default, extends } = texture;
+	let texture = TextureMeta::Version3 { name, description, default, extends };
+	ron2::to_string_pretty(&texture).map_err(Error::SerialiseError)
+}
+
+/// Manifest for a new texture, with nothing but its name and default option set
+pub(crate) fn new_texture(name: &str, default: Option<&str>) -> Result<String> {
+	serialise_texture(TextureUnversioned {
+		name: nm::Name::new(Localised::Plain(name.into())),
+		description: None,
+		default: default.map(|d| nm::Default::new(d.into())),
+		extends: None
+	})
+}
+
+/// Upgrades a manifest to the latest `meta_version`, or `None` if it
+/// already is the latest
+pub(crate) fn upgrade_texture(s: &str) -> Result<Option<String>> {
+	let meta = ron::from_str(s)?;
+	if let TextureMeta::Version3 { .. } = meta { return Ok(None) }
+	serialise_texture(unversion(meta)).map(Some)
+}
+
+/// JSON schema of the manifest, covering every `meta_version`
+pub(crate) fn texture_schema() -> RootSchema {
+	schema_for!(TextureMeta)
+}
+
+fn unversion(meta: TextureMeta) -> TextureUnversioned {
 	use TextureMeta::*;
-	Ok(match ron::from_str(s)? {
+	match meta {
 		Version1 { name, description, default } => {
-			TextureUnversioned { name, description, default }
+			let (name, description) = localise_plain(name, description);
+			let extends = None;
+			TextureUnversioned { name, description, default, extends }
 		}
-	})
+		Version2 { name, description, default, extends } => {
+			let (name, description) = localise_plain(name, description);
+			TextureUnversioned { name, description, default, extends }
+		}
+		Version3 { name, description, default, extends } => {
+			TextureUnversioned { name, description, default, extends }
+		}
+	}
 }
@@ -5,21 +5,28 @@ mod meta;
 mod rt;
 
 pub use self::error::Error;
-pub use self::rt::{ TextureRuntime, FrontendData };
-use super::option2;
-use ::hashbrown::HashMap;
+pub(crate) use self::meta::{ new_texture, texture_schema, upgrade_texture };
+pub use self::rt::{ TextureRuntime, Extends, MergedTexture, FrontendData };
+use super::{ error::load_err, option2, pack2 };
+use super::locale::Localised;
+use ::std::collections::BTreeMap;
 
 ::nominal::nominal_mod! {
 	mod nm {
-		nominal!(pub Name, inner: String);
-		nominal!(pub Description, inner: Option<String>);
-		nominal!(pub Default, inner: Option<String>);
+		nominal!(pub PlainName, inner: String);
+		nominal!(pub PlainDescription, inner: String);
+		nominal!(pub Name, inner: Localised);
+		nominal!(pub Description, inner: Localised);
+		nominal!(pub Default, inner: String);
+		nominal!(pub Extends, inner: String);
 	}
 	pub mod nr {
-		nominal!(pub Name, inner: String);
-		nominal!(pub Description, inner: Option<String>);
+		nominal!(pub Name, inner: Localised);
+		nominal!(pub Description, inner: Option<Localised>);
 		nominal!(pub ID, inner: String);
 		nominal!(pub Default, inner: Option<option2::nr::ID>);
-		nominal!(pub Options, inner: HashMap<option2::nr::ID, option2::OptionRuntime>);
+		nominal!(pub Options, inner: BTreeMap<option2::nr::ID, option2::OptionRuntime>);
+		nominal!(pub Extends, inner: Option<super::Extends>);
+		nominal!(pub Errors, inner: Vec<load_err::Diagnostic>);
 	}
 }
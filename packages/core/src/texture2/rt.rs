@@ -1,10 +1,14 @@
 use ::mc_versions::MCVersionRef;
+use crate::error::load_err::Diagnostic;
+use crate::load::{ load_concurrently, read_entry_names, LoadContext };
 use crate::option2::{ self, OptionRuntime };
+use crate::pack2;
 use crate::util::fs;
 use crate::util::path_builder3::WithTextureID;
+use crate::watch;
 use super::{ meta, nm, nr };
 use super::error::*;
-use ::hashbrown::HashMap;
+use ::std::collections::BTreeMap;
 use ::serde::Serialize;
 
 pub struct TextureRuntime {
@@ -12,27 +16,64 @@ pub struct TextureRuntime {
 	description: nr::Description,
 	id: nr::ID,
 	default: nr::Default,
-	options: nr::Options
+	options: nr::Options,
+	extends: nr::Extends,
+	/// options in this texture that failed to load
+	errors: nr::Errors
+}
+
+/// The texture that a texture extends, in another pack
+///
+/// Extending a texture merges the extending texture's options into it:
+///
+/// - The extended texture has to be in a pack that the extending texture's
+///   pack depends on, and can't itself be extending another texture.
+/// - Options are merged in the order packs are in the workspace, so if
+///   more than one texture has an option with the same ID, the one from the
+///   pack latest in the workspace replaces the others (the same way built
+///   files are layered).
+/// - An extending texture's default has to be one of its own options, and
+///   replaces the extended texture's default (again, latest pack wins).
+/// - Extending textures can't be selected or built on their own, only
+///   through the texture they extend.
+#[derive(Clone, Debug)]
+pub struct Extends {
+	pack_id: pack2::nr::ID,
+	texture_id: nr::ID
+}
+
+impl Extends {
+	#[inline]
+	pub fn pack_id(&self) -> &pack2::nr::ID {
+		&self.pack_id
+	}
+
+	#[inline]
+	pub fn texture_id(&self) -> &nr::ID {
+		&self.texture_id
+	}
 }
 
 impl TextureRuntime {
-	pub(crate) async fn new(p: &WithTextureID<'_>) -> Result<Option<Self>> {
+	pub(crate) async fn new(p: &WithTextureID<'_>, ctx: &mut LoadContext) -> Result<Option<Self>> {
 		let dir = p.texture_dir_silent_fail().await?;
 		let meta_path = p.texture_manifest_silent_fail().await?;
 		let meta_file = fs::read_to_string2(meta_path).await?;
 		let meta::TextureUnversioned {
 			name,
 			description,
-			default
+			default,
+			extends
 		} = meta::deserialise_texture(&meta_file)?;
 
 		let name = name.transmute_nom();
-		let description = description.transmute_nom();
+		let description = nr::Description::new(description.map(nm::Description::into_inner));
 		let id = nr::ID::new(p.texture_id_ref().into());
 		let default = default
-			.map_nom_some(option2::nr::ID::new)
-			.transmute_nom();
-		let options = read_options(p).await?;
+			.map(|d| option2::nr::ID::new(d.into_inner()));
+		let mut default = nr::Default::new(default);
+		let (options, errors) = read_options(p, ctx).await;
+		let extends = process_extends(&id, extends)?;
 
 		// read option dir first,
 		//
@@ -44,60 +85,232 @@ impl TextureRuntime {
 		// keep this comment
 		// TODO consider making nonexistent default a warning
 		// orrrrr maybe even add like, a strictness option that controls this
+		if let Some(d) = default.ref_inner() {
+			if !options.ref_inner().contains_key(d) {
+				let texture_id = id.ref_inner().clone();
+				let default_id = d.ref_inner().clone();
+				ctx.recoverable(&dir, Error::DefaultNotFound { texture_id, default: default_id })?;
+
+				// lenient, so carry on like there's no default
+				default = nr::Default::new(None);
+			}
+		}
 
 		Ok(Some(Self {
 			name,
 			description,
 			id,
 			default,
-			options
+			options,
+			extends,
+			errors
 		}))
 	}
+
+	#[inline]
+	pub fn name(&self) -> &nr::Name {
+		&self.name
+	}
+
+	#[inline]
+	pub fn description(&self) -> &nr::Description {
+		&self.description
+	}
+
+	#[inline]
+	pub fn id(&self) -> &nr::ID {
+		&self.id
+	}
+
+	#[inline]
+	pub fn default(&self) -> &nr::Default {
+		&self.default
+	}
+
+	#[inline]
+	pub fn options(&self) -> &nr::Options {
+		&self.options
+	}
+
+	#[inline]
+	pub fn extends(&self) -> &nr::Extends {
+		&self.extends
+	}
+
+	#[inline]
+	pub(crate) fn option_mut(&mut self, option_id: &option2::nr::ID) -> Option<&mut OptionRuntime> {
+		self.options.mut_inner().get_mut(option_id)
+	}
+
+	/// Replaces the option `id` (in `dir`) with what was just loaded: the
+	/// option, `None` if it's not there anymore, or the error if it failed to
+	/// load. Returns `false` without changing anything if the option is this
+	/// texture's default and it's gone, since then the whole texture needs
+	/// reloading (to check the default again).
+	pub(crate) fn replace_option(
+		&mut self,
+		id: &option2::nr::ID,
+		dir: &str,
+		loaded: Result<Option<OptionRuntime>, Diagnostic>
+	) -> bool {
+		let is_default = self.default.ref_inner().as_ref() == Some(id);
+		if is_default && !matches!(loaded, Ok(Some(_))) { return false }
+
+		let errors = self.errors.mut_inner();
+		errors.retain(|d| !watch::is_in_dir(d.path(), dir));
+
+		let options = self.options.mut_inner();
+		match loaded {
+			Ok(Some(o)) => { options.insert(id.clone(), o); }
+			Ok(None) => { options.remove(id); }
+			Err(e) => {
+				options.remove(id);
+				errors.push(e);
+			}
+		}
+		true
+	}
+}
+
+fn process_extends(texture_id: &nr::ID, extends: Option<nm::Extends>) -> Result<nr::Extends> {
+	let extends = match extends {
+		Some(e) => { e.into_inner() }
+		None => { return Ok(nr::Extends::new(None)) }
+	};
+
+	let (pack_id, extends_texture_id) = match extends.split_once(':') {
+		Some((p, t)) if !p.is_empty() && !t.is_empty() && !t.contains(':') => { (p, t) }
+		_ => {
+			let texture_id = texture_id.ref_inner().clone();
+			return Err(Error::InvalidExtends { texture_id, extends })
+		}
+	};
+
+	let pack_id = pack2::nr::ID::new(pack_id.into());
+	let texture_id = nr::ID::new(extends_texture_id.into());
+	Ok(nr::Extends::new(Some(Extends { pack_id, texture_id })))
 }
 
-async fn read_options(p: &WithTextureID<'_>) -> Result<nr::Options> {
-	let option_entries_dir = p.option_entries_dir_checked().await?;
+/// A texture with the options of all the textures extending it merged in.
+/// See [`Extends`] for how they're merged.
+pub struct MergedTexture<'h> {
+	texture: &'h TextureRuntime,
+	default: Option<&'h option2::nr::ID>,
+	options: BTreeMap<&'h option2::nr::ID, &'h OptionRuntime>,
+	errors: Vec<&'h Diagnostic>
+}
+
+impl<'h> MergedTexture<'h> {
+	/// `extensions` should be in workspace pack order
+	pub fn new<I>(texture: &'h TextureRuntime, extensions: I) -> Self
+	where
+		I: IntoIterator<Item = &'h TextureRuntime>
+	{
+		let mut default = texture.default.ref_inner().as_ref();
+		let mut options = texture.options.ref_inner()
+			.iter()
+			.collect::<BTreeMap<_, _>>();
+		let mut errors = texture.errors.ref_inner()
+			.iter()
+			.collect::<Vec<_>>();
+
+		for extension in extensions {
+			if let Some(d) = extension.default.ref_inner() {
+				default = Some(d);
+			}
+			options.extend(extension.options.ref_inner());
+			errors.extend(extension.errors.ref_inner());
+		}
+
+		Self { texture, default, options, errors }
+	}
+
+	#[inline]
+	pub fn texture(&self) -> &'h TextureRuntime {
+		self.texture
+	}
+
+	#[inline]
+	pub fn default(&self) -> Option<&'h option2::nr::ID> {
+		self.default
+	}
+
+	#[inline]
+	pub fn options(&self) -> &BTreeMap<&'h option2::nr::ID, &'h OptionRuntime> {
+		&self.options
+	}
+
+	/// options that failed to load, in the texture and the textures extending it
+	#[inline]
+	pub fn errors(&self) -> &[&'h Diagnostic] {
+		&self.errors
+	}
+}
+
+async fn read_options(p: &WithTextureID<'_>, ctx: &mut LoadContext) -> (nr::Options, nr::Errors) {
 	let mut options_nom = nr::Options::default();
 	let options = options_nom.mut_inner();
-	let mut read_dir = fs::read_dir2(option_entries_dir).await?;
-
-	while let Some(file) = read_dir.next().await? {
-		let file_name = file.file_name();
-		let p = p.clone().with_option_id_osstr(&file_name)?;
+	let mut errors = Vec::new();
+	let option_ids = match p.option_entries_dir_checked().await {
+		Ok(dir) => { read_entry_names(ctx, dir).await }
+		Err(e) => {
+			ctx.error(p.texture_dir_unchecked(), e);
+			Vec::new()
+		}
+	};
+	let results = load_concurrently(ctx, &option_ids, |option_id, mut ctx| async move {
+		let p = p.clone().with_option_id(option_id);
+		let result = OptionRuntime::new(&p, &mut ctx).await;
+		((p, result), ctx)
+	}).await;
 
-		// TODO
-		if let Some(o) = OptionRuntime::new(&p).await? {
-			let id = option2::nr::ID::new(p.option_id_ref().into());
-			options.insert(id, o);
+	for ((p, result), option_ctx) in results {
+		ctx.merge(option_ctx);
+		match result {
+			Ok(Some(o)) => {
+				let id = option2::nr::ID::new(p.option_id_ref().into());
+				options.insert(id, o);
+			}
+			Ok(None) => {}
+			Err(e) => { errors.push(ctx.error(p.option_dir_unchecked(), e).clone()) }
 		}
 	}
 
-	Ok(options_nom)
+	(options_nom, nr::Errors::new(errors))
 }
 
 #[derive(Serialize)]
 pub struct FrontendData<'h> {
-	name: &'h nr::Name,
-	description: &'h nr::Description,
+	name: &'h str,
+	description: Option<&'h str>,
 	id: &'h nr::ID,
-	default: &'h nr::Default,
-	options: HashMap<&'h str, option2::FrontendData<'h>>
+	default: Option<&'h option2::nr::ID>,
+	/// `pack_id:texture_id` of the texture this one extends. The options of
+	/// extending textures are already merged into the texture they extend
+	extends: Option<String>,
+	options: BTreeMap<&'h str, option2::FrontendData<'h>>,
+	/// options that failed to load, so they can be shown as broken
+	errors: Vec<&'h Diagnostic>
 }
 
 impl<'h> FrontendData<'h> {
-	pub fn new(texture: &'h TextureRuntime, mc_version: MCVersionRef) -> Self {
-		let name = &texture.name;
-		let description = &texture.description;
-		let id = &texture.id;
-		let default = &texture.default;
-		let options = texture.options.ref_inner()
+	pub fn new(texture: &MergedTexture<'h>, mc_version: MCVersionRef, locales: &[String]) -> Self {
+		let name = texture.texture.name.ref_inner().get(locales);
+		let description = texture.texture.description.ref_inner().as_ref().map(|d| d.get(locales));
+		let id = &texture.texture.id;
+		let default = texture.default;
+		let extends = texture.texture.extends.ref_inner()
+			.as_ref()
+			.map(|e| format!("{}:{}", e.pack_id.ref_inner(), e.texture_id.ref_inner()));
+		let options = texture.options
 			.iter()
 			.map(|(id, o)| (
 				&**id.ref_inner(),
-				option2::FrontendData::new(o, mc_version)
+				option2::FrontendData::new(o, mc_version, locales)
 			))
 			.collect();
+		let errors = texture.errors.clone();
 
-		Self { name, description, id, default, options }
+		Self { name, description, id, default, extends, options, errors }
 	}
 }
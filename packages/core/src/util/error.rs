@@ -27,6 +27,18 @@ pub enum Error {
 		path_name: String
 	},
 
+	#[error("invalid {path_name}: {path} (has to be {expected})")]
+	InvalidPath {
+		path: String,
+		path_name: String,
+		expected: &'static str
+	},
+
+	#[error("{path} is a dir that was already found through another path (ex. a symlink to a dir it's in)")]
+	DirFoundTwice {
+		path: String
+	},
+
 	#[error("error parsing ron:\n{0}")]
 	RonError(#[from] ron::error::Error),
 
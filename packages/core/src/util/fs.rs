@@ -4,6 +4,7 @@ use crate::nom as n;
 use super::error::*;
 use ::std::fs;
 use ::std::io::Read as _;
+use ::std::path::PathBuf;
 
 #[inline]
 pub async fn metadata(path: n::global::Path) -> Result<fs::Metadata> {
@@ -37,11 +38,28 @@ pub async fn is_file2(path: String) -> Result<bool> {
 	Ok(metadata2(path).await?.is_file())
 }
 
+/// The absolute path of `path`, with symlinks resolved
+pub async fn canonicalize2(path: String) -> Result<String> {
+	let f = || fs::canonicalize(path)
+		.map_err(Error::FSError)?
+		.into_os_string()
+		.into_string()
+		.map_err(|_| Error::NonUtf8Path);
+	spawn_blocking(f).await
+}
+
 #[inline]
 pub async fn read_to_string(path: n::global::FilePath) -> Result<String> {
 	read_to_string2(path.into_inner()).await
 }
 
+#[inline]
+pub async fn read2(path: String) -> Result<Vec<u8>> {
+	let f = || fs::read(path)
+		.map_err(Error::FSError);
+	spawn_blocking(f).await
+}
+
 // TODO: can probably be optimised (one less meta call?) if rewritten by hand?
 pub async fn read_to_string2(path: String) -> Result<String> {
 	let f = || fs::read(path)
@@ -61,18 +79,58 @@ pub async fn read_dir(path: n::global::DirPath) -> Result<ReadDir> {
 
 #[inline]
 pub async fn read_dir2(path: String) -> Result<ReadDir> {
+	let _permit = super::fs2::blocking_permit().await;
 	tokio::fs::read_dir(path)
 		.await
 		.map(ReadDir)
 		.map_err(Error::FSError)
 }
 
+/// Gets the type of an entry from [`ReadDir`], which might need another call
+/// to the filesystem
+#[inline]
+pub async fn file_type2(entry: &tokio::fs::DirEntry) -> Result<fs::FileType> {
+	let _permit = super::fs2::blocking_permit().await;
+	entry.file_type()
+		.await
+		.map_err(Error::FSError)
+}
+
+/// Reads the names of the entries in a directory, sorted, so they're in the
+/// same order on every platform. Names that aren't valid UTF-8 can't be used,
+/// so they're skipped, and their paths passed to `non_utf8` to be reported.
+pub async fn read_dir_names2<F>(path: String, non_utf8: F) -> Result<Vec<String>>
+where
+	F: FnMut(PathBuf)
+{
+	let f = move || {
+		let mut names = Vec::new();
+		let mut non_utf8 = Vec::new();
+
+		for entry in fs::read_dir(path).map_err(Error::FSError)? {
+			let entry = entry.map_err(Error::FSError)?;
+			match entry.file_name().into_string() {
+				Ok(name) => { names.push(name) }
+				Err(_) => { non_utf8.push(entry.path()) }
+			}
+		}
+
+		Ok((names, non_utf8))
+	};
+	let (mut names, skipped) = spawn_blocking(f).await?;
+
+	skipped.into_iter().for_each(non_utf8);
+	names.sort_unstable();
+	Ok(names)
+}
+
 #[inline]
 async fn spawn_blocking<F, T>(f: F) -> Result<T>
 where
 	F: FnOnce() -> Result<T> + Send + 'static,
 	T: Send + 'static
 {
+	let _permit = super::fs2::blocking_permit().await;
 	match ::tokio::task::spawn_blocking(f).await {
 		Ok(r) => { r }
 		Err(e) => { Err(Error::BackgroundTaskFailed(e)) }
@@ -85,6 +143,7 @@ pub struct ReadDir(tokio::fs::ReadDir);
 impl ReadDir {
 	#[inline]
 	pub async fn next(&mut self) -> Result<Option<tokio::fs::DirEntry>> {
+		let _permit = super::fs2::blocking_permit().await;
 		self.0.next_entry()
 			.await
 			.map_err(Error::FSError)
@@ -1,7 +1,15 @@
 use crate::error::*;
+use crate::error::common::WithPath;
 use crate::nom as n;
 use ::std::fs;
-use ::std::io::Read as _;
+use ::std::io::{ Read as _, Write as _ };
+use ::tokio::sync::{ Semaphore, SemaphorePermit };
+
+/// How many blocking filesystem calls are done at once, across everything
+/// (including all the entries of packs being loaded concurrently)
+pub(crate) const CONCURRENCY_LIMIT: usize = 32;
+
+static BLOCKING_PERMITS: Semaphore = Semaphore::const_new(CONCURRENCY_LIMIT);
 
 pub async fn metadata<F>(path_fn: F) -> Result<fs::Metadata, fs_err::MetadataWithPath>
 where
@@ -14,6 +22,30 @@ where
 	).await.map_err(|e| e.with_path(path_fn()))
 }
 
+/// Like [`metadata`], but doesn't follow symlinks
+pub async fn symlink_metadata<F>(path_fn: F) -> Result<fs::Metadata, fs_err::MetadataWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	spawn_blocking(
+		|| fs::symlink_metadata(path).map_err(fs_err::metadata_fs),
+		fs_err::metadata_join
+	).await.map_err(|e| e.with_path(path_fn()))
+}
+
+/// Like [`metadata`], but a path that doesn't exist isn't an error
+pub async fn exists<F>(path_fn: F) -> Result<bool, fs_err::MetadataWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	spawn_blocking(
+		move || ::std::path::Path::new(&path).try_exists().map_err(fs_err::metadata_fs),
+		fs_err::metadata_join
+	).await.map_err(|e| e.with_path(path_fn()))
+}
+
 pub async fn is_dir<F>(path_fn: F) -> Result<bool, fs_err::IsDir>
 where
 	F: Fn() -> String
@@ -56,10 +88,107 @@ where
 	}
 }
 
+pub async fn write<F>(path_fn: F, contents: Vec<u8>) -> Result<(), fs_err::WriteWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	spawn_blocking(
+		|| fs::write(path, contents).map_err(fs_err::write_fs),
+		fs_err::write_join
+	).await.map_err(|e| WithPath::new(e, path_fn()))
+}
+
+/// Writes a file that doesn't exist yet, returning `false` (and writing
+/// nothing) if it does, so nothing can create it in between checking and
+/// writing
+pub async fn write_new<F>(path_fn: F, contents: Vec<u8>) -> Result<bool, fs_err::WriteWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	let f = move || {
+		let file = fs::OpenOptions::new()
+			.write(true)
+			.create_new(true)
+			.open(path);
+		let mut file = match file {
+			Ok(file) => { file }
+			Err(e) if e.kind() == ::std::io::ErrorKind::AlreadyExists => { return Ok(false) }
+			Err(e) => { return Err(fs_err::write_fs(e)) }
+		};
+		file.write_all(&contents).map_err(fs_err::write_fs)?;
+		Ok(true)
+	};
+	spawn_blocking(f, fs_err::write_join)
+		.await
+		.map_err(|e| WithPath::new(e, path_fn()))
+}
+
+/// Creates a file to write into, replacing it if it exists already
+pub async fn create<F>(path_fn: F) -> Result<fs::File, fs_err::WriteWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	spawn_blocking(
+		|| fs::File::create(path).map_err(fs_err::write_fs),
+		fs_err::write_join
+	).await.map_err(|e| WithPath::new(e, path_fn()))
+}
+
+pub async fn create_dir_all<F>(path_fn: F) -> Result<(), fs_err::CreateDirAllWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	spawn_blocking(
+		|| fs::create_dir_all(path).map_err(fs_err::create_dir_all_fs),
+		fs_err::create_dir_all_join
+	).await.map_err(|e| WithPath::new(e, path_fn()))
+}
+
+/// Moves the file at `from` to `to`, replacing it if it exists already. In the
+/// same directory, this is atomic.
+pub async fn rename<F, T>(from_fn: F, to_fn: T) -> Result<(), fs_err::RenameWithPath>
+where
+	F: Fn() -> String,
+	T: Fn() -> String
+{
+	let (from, to) = (from_fn(), to_fn());
+	spawn_blocking(
+		move || fs::rename(&from, to).map_err(|e| fs_err::rename_fs(e, from)),
+		|e| fs_err::rename_join(e, from_fn())
+	).await.map_err(|e| WithPath::new(e, to_fn()))
+}
+
+pub async fn remove_file<F>(path_fn: F) -> Result<(), fs_err::RemoveWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	spawn_blocking(
+		|| fs::remove_file(path).map_err(fs_err::remove_fs),
+		fs_err::remove_join
+	).await.map_err(|e| WithPath::new(e, path_fn()))
+}
+
+pub async fn remove_dir_all<F>(path_fn: F) -> Result<(), fs_err::RemoveWithPath>
+where
+	F: Fn() -> String
+{
+	let path = path_fn();
+	spawn_blocking(
+		|| fs::remove_dir_all(path).map_err(fs_err::remove_fs),
+		fs_err::remove_join
+	).await.map_err(|e| WithPath::new(e, path_fn()))
+}
+
 pub async fn read_dir<F>(path_fn: F) -> Result<ReadDir<F>, fs_err::ReadDirWithPath>
 where
 	F: Fn() -> String
 {
+	let _permit = blocking_permit().await;
 	match tokio::fs::read_dir(path_fn()).await {
 		Ok(read_dir) => { Ok(ReadDir { read_dir, path_fn }) }
 		Err(e) => { Err(fs_err::read_dir(e, path_fn())) }
@@ -73,12 +202,21 @@ where
 	F: FnOnce() -> Result<T, E> + Send + 'static,
 	EF: FnOnce(fs_err::SpawnBlocking) -> E
 {
+	let _permit = blocking_permit().await;
 	match ::tokio::task::spawn_blocking(f).await {
 		Ok(r) => { r }
 		Err(e) => { Err(e_fn(fs_err::spawn_blocking(e))) }
 	}
 }
 
+/// Waits for a free slot to run a blocking filesystem call in
+#[inline]
+pub(crate) async fn blocking_permit() -> SemaphorePermit<'static> {
+	BLOCKING_PERMITS.acquire()
+		.await
+		.expect("semaphore is never closed")
+}
+
 pub struct ReadDir<F> {
 	read_dir: tokio::fs::ReadDir,
 	path_fn: F
@@ -90,6 +228,7 @@ where
 	F: Fn() -> String
 {
 	pub async fn next(&mut self) -> Result<Option<tokio::fs::DirEntry>, fs_err::ReadDirEntryWithPath> {
+		let _permit = blocking_permit().await;
 		self.read_dir.next_entry()
 			.await
 			.map_err(|e| fs_err::read_dir_entry(e, (self.path_fn)()))
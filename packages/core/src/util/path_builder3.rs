@@ -12,14 +12,14 @@ use ::std::result::Result as StdResult;
 
 // -- consts --
 
-const ROOT_MANIFEST: &str = "pack.wiwimeta";
+pub(crate) const ROOT_MANIFEST: &str = "pack.wiwimeta";
 
-const TEXTURE_ENTRIES_DIR: &str = "textures";
-const TEXTURE_MANIFEST: &str = "texture.wiwimeta";
+pub(crate) const TEXTURE_ENTRIES_DIR: &str = "textures";
+pub(crate) const TEXTURE_MANIFEST: &str = "texture.wiwimeta";
 
-const OPTION_MANIFEST: &str = "option.wiwimeta";
+pub(crate) const OPTION_MANIFEST: &str = "option.wiwimeta";
 
-const PROVIDER_MANIFEST: &str = "provider.wiwimeta";
+pub(crate) const PROVIDER_MANIFEST: &str = "provider.wiwimeta";
 
 // -- structs --
 
@@ -413,6 +413,56 @@ impl<'h> WithProviderID<'h> {
 	}
 }
 
+/// paths that aren't checked, for reporting where problems were found, or
+/// for when they're checked separately
+impl<'h> WithRootDir<'h> {
+	#[inline]
+	pub fn root_manifest_unchecked(&self) -> String {
+		self._root_manifest().into_string()
+	}
+
+	#[inline]
+	pub fn texture_entries_dir_unchecked(&self) -> String {
+		self._texture_entries_dir().into_string()
+	}
+}
+
+impl<'h> WithTextureID<'h> {
+	#[inline]
+	pub fn texture_dir_unchecked(&self) -> String {
+		self._texture_dir().into_string()
+	}
+
+	#[inline]
+	pub fn texture_manifest_unchecked(&self) -> String {
+		self._texture_manifest().into_string()
+	}
+}
+
+impl<'h> WithOptionID<'h> {
+	#[inline]
+	pub fn option_dir_unchecked(&self) -> String {
+		self._option_dir().into_string()
+	}
+
+	#[inline]
+	pub fn option_manifest_unchecked(&self) -> String {
+		self._option_manifest().into_string()
+	}
+}
+
+impl<'h> WithProviderID<'h> {
+	#[inline]
+	pub fn provider_dir_unchecked(&self) -> String {
+		self._provider_dir().into_string()
+	}
+
+	#[inline]
+	pub fn provider_manifest_unchecked(&self) -> String {
+		self._provider_manifest().into_string()
+	}
+}
+
 // -- SilentFailingPath custom result type --
 
 pub enum SilentFailingPath {
@@ -0,0 +1,47 @@
+//! Checks for paths that come from manifests or resource packs, before they're
+//! joined onto a dir
+
+use ::camino::{ Utf8Component, Utf8Path };
+
+/// Whether `path` is relative and already normalised (no empty, `.` or `..`
+/// segments), so joining it onto a dir can't end up outside that dir
+pub fn is_normalised_relative(path: &str) -> bool {
+	!path.is_empty()
+		&& path.split('/').all(|s| !matches!(s, "" | "." | ".."))
+		&& !path.contains('\\')
+		&& Utf8Path::new(path).components().all(|c| matches!(c, Utf8Component::Normal(_)))
+}
+
+/// Whether `namespace` is a valid resource location namespace
+pub fn is_resource_namespace(namespace: &str) -> bool {
+	is_normalised_relative(namespace)
+		&& namespace.chars().all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.'))
+}
+
+/// Whether `path` is a valid resource location path (one or more segments,
+/// separated by `/`). Minecraft only loads assets from paths like these
+pub fn is_resource_path(path: &str) -> bool {
+	is_normalised_relative(path)
+		&& path.chars().all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.' | '/'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_escaping_paths() {
+		assert!(is_normalised_relative("files"));
+		assert!(is_normalised_relative("files/extra/a.png"));
+
+		for path in ["", "/etc", "..", "../x", "a/../../x", "a/./b", "a//b", "a/", "C:\\x", "a\\..\\b"] {
+			assert!(!is_normalised_relative(path), "{path}");
+		}
+
+		assert!(is_resource_namespace("my-mod"));
+		assert!(!is_resource_namespace("my/mod"));
+		assert!(is_resource_path("textures/block"));
+		assert!(!is_resource_path("Textures/block"));
+		assert!(!is_resource_path("textures/../.."));
+	}
+}
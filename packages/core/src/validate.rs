@@ -0,0 +1,215 @@
+//! Checking pack sources for problems that loading doesn't report, for linting
+//! packs (ex. before committing them)
+
+use crate::{ option2, provider2, texture2 };
+use crate::error::load_err::{ self, Diagnostic };
+use crate::error::validate_err::{ self, ValidatePack };
+use crate::pack2::PackRuntime;
+use crate::util::create_path_builder3;
+use crate::util::fs2;
+use crate::util::path_builder3::{ OPTION_MANIFEST, PROVIDER_MANIFEST, TEXTURE_MANIFEST };
+use crate::util::safe_path;
+use ::std::collections::BTreeSet;
+
+/// Checks the source of a loaded pack, returning everything found wrong with
+/// it: the problems found while loading it (load it in lenient mode, so it
+/// still loads with broken parts, which are reported here instead), then
+///
+/// - dirs without a manifest, which can't be loaded
+/// - files that aren't used by anything (as warnings)
+/// - files that are copied to an asset path minecraft can't load (ex. one with
+///   uppercase letters). Only copy-files providers copy files from their dir,
+///   so those are the only ones this is checked for.
+///
+/// Providers that failed to load are already reported, so their files aren't
+/// checked.
+pub async fn validate_pack(pack: &PackRuntime) -> Result<Vec<Diagnostic>, ValidatePack> {
+	let mut diagnostics = pack.diagnostics().ref_inner().clone();
+	let p = create_path_builder3().with_root_dir(pack.dir().ref_inner());
+
+	let texture_ids = check_entries(&mut diagnostics, p.texture_entries_dir_unchecked(), None, TEXTURE_MANIFEST).await?;
+	for texture_id in &texture_ids {
+		let p = p.clone().with_texture_id(texture_id);
+		let texture = pack.textures().ref_inner().get(&texture2::nr::ID::new(texture_id.clone()));
+
+		let option_ids = check_entries(&mut diagnostics, p.texture_dir_unchecked(), Some(TEXTURE_MANIFEST), OPTION_MANIFEST).await?;
+		for option_id in &option_ids {
+			let p = p.clone().with_option_id(option_id);
+			let option = texture.and_then(|t| t.options().ref_inner().get(&option2::nr::ID::new(option_id.clone())));
+
+			let provider_ids = check_entries(&mut diagnostics, p.option_dir_unchecked(), Some(OPTION_MANIFEST), PROVIDER_MANIFEST).await?;
+			for provider_id in &provider_ids {
+				let p = p.clone().with_provider_id(provider_id);
+				let provider = option.and_then(|o| o.providers().ref_inner().get(&provider2::nr::ID::new(provider_id.clone())));
+
+				if let Some(provider) = provider {
+					check_provider(&mut diagnostics, p.provider_dir_unchecked(), provider).await?;
+				}
+			}
+		}
+	}
+
+	Ok(diagnostics)
+}
+
+/// Checks the dir of a pack's textures, a texture, or an option. Other than
+/// the dir's own manifest, these should only have dirs in them, each with a
+/// `child_manifest`. Returns the names of the dirs that have one.
+async fn check_entries(
+	diagnostics: &mut Vec<Diagnostic>,
+	dir: String,
+	manifest: Option<&str>,
+	child_manifest: &'static str
+) -> Result<Vec<String>, ValidatePack> {
+	let mut names = Vec::new();
+
+	for (name, path, is_dir) in read_entries(dir).await? {
+		if is_dir {
+			let has_manifest = read_entries(path.clone()).await?
+				.iter()
+				.any(|(name, _, is_dir)| name == child_manifest && !is_dir);
+			if has_manifest {
+				names.push(name);
+			} else {
+				// loading either skips these (through the `*_silent_fail` checks,
+				// if the manifest is a dir) or fails with a not found error, which
+				// this replaces, since it says what's actually wrong
+				diagnostics.retain(|d| d.path() != path);
+				diagnostics.push(load_err::error(path, validate_err::missing_manifest(child_manifest)));
+			}
+		} else if Some(&*name) != manifest {
+			diagnostics.push(load_err::warning(path, validate_err::unreferenced_file()));
+		}
+	}
+
+	Ok(names)
+}
+
+/// Checks every file in a provider's dir is used by its generator, and that
+/// the ones it copies into the pack end up somewhere minecraft can load them
+async fn check_provider(
+	diagnostics: &mut Vec<Diagnostic>,
+	dir: String,
+	provider: &provider2::ProviderRuntime
+) -> Result<(), ValidatePack> {
+	let copies = provider.gen().copies();
+
+	for (source, asset_path) in &copies {
+		if !safe_path::is_resource_path(asset_path) {
+			let path = format!("{dir}/{source}");
+			diagnostics.push(load_err::error(path, validate_err::invalid_asset_path(asset_path.clone())));
+		}
+	}
+
+	let used = copies.into_iter()
+		.map(|(source, _)| source)
+		.chain([PROVIDER_MANIFEST.into()])
+		.collect::<BTreeSet<_>>();
+
+	// dirs still to read, relative to the provider dir ("" being itself)
+	let mut dirs = vec![String::new()];
+	while let Some(relative_dir) = dirs.pop() {
+		let full_dir = match &*relative_dir {
+			"" => { dir.clone() }
+			relative_dir => { format!("{dir}/{relative_dir}") }
+		};
+
+		for (name, path, is_dir) in read_entries(full_dir).await? {
+			let relative = match &*relative_dir {
+				"" => { name }
+				relative_dir => { format!("{relative_dir}/{name}") }
+			};
+
+			if is_dir {
+				dirs.push(relative);
+			} else if !used.contains(&relative) {
+				diagnostics.push(load_err::warning(path, validate_err::unreferenced_file()));
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Entries in `dir` as (name, path, is dir), sorted by name. Hidden entries
+/// (ex. `.DS_Store`, `.gitkeep`) are left out, since they're not part of the pack.
+async fn read_entries(dir: String) -> Result<Vec<(String, String, bool)>, ValidatePack> {
+	let mut read_dir = fs2::read_dir(|| dir.clone()).await?;
+	let mut entries = Vec::new();
+
+	while let Some(entry) = read_dir.next().await? {
+		let name = entry.file_name()
+			.into_string()
+			.map_err(|_| validate_err::non_utf8_path(entry.path().to_string_lossy().into_owned()))?;
+		if name.starts_with('.') { continue }
+
+		let path = format!("{dir}/{name}");
+		let is_dir = fs2::is_dir(|| path.clone()).await?;
+		entries.push((name, path, is_dir));
+	}
+
+	entries.sort_unstable();
+	Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::error::load_err::Severity;
+	use crate::load::Strictness;
+	use crate::test_util;
+	use crate::workspace2::{ self, WorkspaceRuntime };
+
+	async fn validate(dir: &str) -> Vec<(Severity, String, String)> {
+		let mut workspace = WorkspaceRuntime::new(workspace2::nr::Name::new("uwu".into()));
+		workspace.set_strictness(Strictness::Lenient);
+		workspace.add_pack(dir).await.unwrap();
+
+		let pack = workspace.packs().next().unwrap();
+		validate_pack(pack).await
+			.unwrap()
+			.into_iter()
+			.map(|d| (d.severity(), d.path().strip_prefix(dir).unwrap().into(), d.message().into()))
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn fixture_is_valid() {
+		assert!(validate(&test_util::fixture("basic")).await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn structural_problems_reported() {
+		let scratch = test_util::scratch_copy("basic", "validate");
+		let dir = scratch.to_str().unwrap();
+
+		let dirt = scratch.join("textures/dirt");
+		::std::fs::create_dir(dirt.join("no-manifest")).unwrap();
+		::std::fs::write(dirt.join("notes.txt"), "uwu").unwrap();
+		::std::fs::write(dirt.join(".DS_Store"), "").unwrap();
+		::std::fs::write(dirt.join("handmade/all/files/Dirt.png"), "").unwrap();
+		::std::fs::write(scratch.join("textures/stone/random/all/stone.png"), "").unwrap();
+
+		// unknown versions already fail loading the provider, in any mode
+		let provider = scratch.join("textures/stone/plain/all/provider.wiwimeta");
+		let manifest = ::std::fs::read_to_string(&provider).unwrap().replace("1.20.4", "1.99.99");
+		::std::fs::write(&provider, manifest).unwrap();
+
+		let problems = validate(dir).await;
+		let paths = problems.iter()
+			.map(|(severity, path, _)| (*severity, &**path))
+			.collect::<Vec<_>>();
+		assert_eq!(vec![
+			(Severity::Error, "/textures/stone/plain/all"),
+			(Severity::Error, "/textures/dirt/no-manifest"),
+			(Severity::Warning, "/textures/dirt/notes.txt"),
+			(Severity::Error, "/textures/dirt/handmade/all/files/Dirt.png"),
+			(Severity::Warning, "/textures/stone/random/all/stone.png")
+		], paths);
+		assert!(problems[0].2.contains("1.99.99"));
+		assert!(problems[1].2.contains("option.wiwimeta"));
+		assert!(problems[3].2.contains("assets/minecraft/textures/block/Dirt.png"));
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+}
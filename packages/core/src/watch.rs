@@ -0,0 +1,346 @@
+//! Watching pack source directories for changes, and working out which
+//! texture, option or provider a changed file is in, so only that part of the
+//! pack needs reloading
+
+use crate::{ option2, pack2, provider2, texture2 };
+use crate::error::watch_err;
+use crate::util::path_builder3::{ OPTION_MANIFEST, TEXTURE_ENTRIES_DIR, TEXTURE_MANIFEST };
+use ::hashbrown::HashSet;
+use ::notify::{ RecommendedWatcher, RecursiveMode, Watcher as _ };
+use ::std::collections::BTreeSet;
+use ::std::path::{ Component, Path, PathBuf };
+use ::std::time::{ Duration, Instant };
+use ::tokio::sync::mpsc::{ self, UnboundedReceiver };
+use ::tokio::time::timeout_at;
+
+/// Part of a pack, from the whole pack down to a single provider
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Entry {
+	Pack,
+	Texture(texture2::nr::ID),
+	Option(texture2::nr::ID, option2::nr::ID),
+	Provider(texture2::nr::ID, option2::nr::ID, provider2::nr::ID)
+}
+
+/// An entry in a pack with files that changed
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Changed {
+	pack_id: pack2::nr::ID,
+	entry: Entry
+}
+
+impl Entry {
+	/// Works out the entry `path` is in, if it's in `pack_dir`. Files that
+	/// aren't in a texture (including the pack manifest) are the whole pack.
+	pub fn for_path(pack_dir: &Path, path: &Path) -> Option<Self> {
+		let relative = path.strip_prefix(pack_dir).ok()?;
+		let mut names = Vec::with_capacity(4);
+		for component in relative.components() {
+			match component {
+				Component::Normal(name) => { names.push(name.to_str()?) }
+				_ => { return None }
+			}
+		}
+
+		let texture = |t: &str| texture2::nr::ID::new(t.into());
+		let option = |o: &str| option2::nr::ID::new(o.into());
+		let provider = |p: &str| provider2::nr::ID::new(p.into());
+
+		Some(match &*names {
+			[TEXTURE_ENTRIES_DIR, t] | [TEXTURE_ENTRIES_DIR, t, TEXTURE_MANIFEST] => {
+				Self::Texture(texture(t))
+			}
+			[TEXTURE_ENTRIES_DIR, t, o] | [TEXTURE_ENTRIES_DIR, t, o, OPTION_MANIFEST] => {
+				Self::Option(texture(t), option(o))
+			}
+			[TEXTURE_ENTRIES_DIR, t, o, p, ..] => {
+				Self::Provider(texture(t), option(o), provider(p))
+			}
+			_ => { Self::Pack }
+		})
+	}
+
+	/// The entry this one is in, or `None` for the whole pack
+	pub fn parent(&self) -> Option<Self> {
+		match self {
+			Self::Pack => { None }
+			Self::Texture(..) => { Some(Self::Pack) }
+			Self::Option(t, ..) => { Some(Self::Texture(t.clone())) }
+			Self::Provider(t, o, ..) => { Some(Self::Option(t.clone(), o.clone())) }
+		}
+	}
+
+	/// Checks if `other` is this entry, or is in it
+	pub fn contains(&self, other: &Self) -> bool {
+		let mut other = Some(other.clone());
+		while let Some(o) = other {
+			if o == *self { return true }
+			other = o.parent();
+		}
+		false
+	}
+}
+
+impl Changed {
+	#[inline]
+	pub fn new(pack_id: pack2::nr::ID, entry: Entry) -> Self {
+		Self { pack_id, entry }
+	}
+
+	#[inline]
+	pub fn pack_id(&self) -> &pack2::nr::ID {
+		&self.pack_id
+	}
+
+	#[inline]
+	pub fn entry(&self) -> &Entry {
+		&self.entry
+	}
+}
+
+impl ::std::fmt::Display for Changed {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+		let pack_id = self.pack_id.ref_inner();
+		match &self.entry {
+			Entry::Pack => { write!(f, "{pack_id}") }
+			Entry::Texture(t) => { write!(f, "{pack_id}/{t}") }
+			Entry::Option(t, o) => { write!(f, "{pack_id}/{t}/{o}") }
+			Entry::Provider(t, o, p) => { write!(f, "{pack_id}/{t}/{o}/{p}") }
+		}
+	}
+}
+
+/// Drops entries that are in another entry in `changed` (since reloading that
+/// one reloads them too), and duplicates, keeping the order otherwise
+pub(crate) fn outermost(changed: Vec<Changed>) -> Vec<Changed> {
+	let mut outermost = Vec::<Changed>::with_capacity(changed.len());
+	for c in changed {
+		let covered = outermost.iter()
+			.any(|o| o.pack_id == c.pack_id && o.entry.contains(&c.entry));
+		if covered { continue }
+
+		outermost.retain(|o| !(o.pack_id == c.pack_id && c.entry.contains(&o.entry)));
+		outermost.push(c);
+	}
+	outermost
+}
+
+/// Checks if `path` (of a diagnostic) is `dir` or something in it
+pub(crate) fn is_in_dir(path: &str, dir: &str) -> bool {
+	match path.strip_prefix(dir) {
+		Some(rest) => { rest.is_empty() || rest.starts_with('/') }
+		None => { false }
+	}
+}
+
+/// Watches directories for changes using the OS's file change notifications
+/// (inotify on linux). Changes are held back until nothing has changed for a
+/// while, since saving a file (or a few) usually comes with a burst of changes.
+pub struct Watcher {
+	watcher: RecommendedWatcher,
+	watched: HashSet<String>,
+	receiver: UnboundedReceiver<PathBuf>,
+	debouncer: Debouncer
+}
+
+impl Watcher {
+	/// Changes are returned once none have been seen for `quiet`
+	pub fn new(quiet: Duration) -> Result<Self, watch_err::Watch> {
+		let (sender, receiver) = mpsc::unbounded_channel();
+
+		// this callback runs in notify's own thread
+		let watcher = ::notify::recommended_watcher(move |event: ::notify::Result<::notify::Event>| {
+			let event = match event {
+				Ok(event) => { event }
+				Err(_) => { return }
+			};
+			if event.kind.is_access() { return }
+
+			for path in event.paths {
+				// the receiving end only goes away when this is being dropped
+				let _ = sender.send(path);
+			}
+		}).map_err(watch_err::create)?;
+
+		let watched = HashSet::new();
+		let debouncer = Debouncer::new(quiet);
+		Ok(Self { watcher, watched, receiver, debouncer })
+	}
+
+	/// Starts watching `dirs` (recursively), and stops watching any
+	/// directories not in it
+	pub fn watch(&mut self, dirs: &[String]) -> Result<(), watch_err::Watch> {
+		let dirs = dirs.iter().cloned().collect::<HashSet<_>>();
+
+		for dir in self.watched.difference(&dirs) {
+			// it might have been deleted already
+			let _ = self.watcher.unwatch(Path::new(dir));
+		}
+		for dir in dirs.difference(&self.watched) {
+			self.watcher.watch(Path::new(dir), RecursiveMode::Recursive)
+				.map_err(|e| watch_err::watch(dir.clone(), e))?;
+		}
+
+		self.watched = dirs;
+		Ok(())
+	}
+
+	/// Waits for changes, returning the paths that changed once it's been
+	/// quiet for long enough. Nothing is lost if this is cancelled (ex. in a
+	/// `select!`), changes seen so far are returned by the next call.
+	pub async fn next(&mut self) -> Vec<PathBuf> {
+		loop {
+			let path = match self.debouncer.deadline() {
+				Some(deadline) => {
+					timeout_at(deadline.into(), self.receiver.recv()).await.ok().flatten()
+				}
+				// the sender is in `watcher`, so this is never `None`
+				None => { self.receiver.recv().await }
+			};
+
+			if let Some(path) = path {
+				self.debouncer.push(vec![path], Instant::now());
+			}
+			if let Some(paths) = self.debouncer.take(Instant::now()) { return paths }
+		}
+	}
+}
+
+/// Collects changed paths until none have come in for `quiet`
+struct Debouncer {
+	quiet: Duration,
+	pending: BTreeSet<PathBuf>,
+	last_change: Option<Instant>
+}
+
+impl Debouncer {
+	#[inline]
+	fn new(quiet: Duration) -> Self {
+		let pending = BTreeSet::new();
+		let last_change = None;
+		Self { quiet, pending, last_change }
+	}
+
+	fn push(&mut self, paths: Vec<PathBuf>, now: Instant) {
+		if paths.is_empty() { return }
+		self.pending.extend(paths);
+		self.last_change = Some(now);
+	}
+
+	/// When the pending changes can be taken, if there are any
+	fn deadline(&self) -> Option<Instant> {
+		self.last_change.map(|last_change| last_change + self.quiet)
+	}
+
+	fn take(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+		let last_change = self.last_change?;
+		if now.duration_since(last_change) < self.quiet { return None }
+
+		self.last_change = None;
+		Some(::std::mem::take(&mut self.pending).into_iter().collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_util;
+	use ::std::fs;
+	use ::tokio::time::timeout;
+
+	fn entry(path: &str) -> Option<Entry> {
+		Entry::for_path("/packs/basic".as_ref(), path.as_ref())
+	}
+
+	fn changed(pack_id: &str, entry: Entry) -> Changed {
+		Changed::new(pack2::nr::ID::new(pack_id.into()), entry)
+	}
+
+	#[test]
+	fn paths_map_to_entries() {
+		let t = || texture2::nr::ID::new("stone".into());
+		let o = || option2::nr::ID::new("plain".into());
+		let p = || provider2::nr::ID::new("all".into());
+
+		assert_eq!(Some(Entry::Pack), entry("/packs/basic/pack.wiwimeta"));
+		assert_eq!(Some(Entry::Pack), entry("/packs/basic/textures"));
+		assert_eq!(Some(Entry::Pack), entry("/packs/basic/readme.md"));
+		assert_eq!(Some(Entry::Texture(t())), entry("/packs/basic/textures/stone"));
+		assert_eq!(Some(Entry::Texture(t())), entry("/packs/basic/textures/stone/texture.wiwimeta"));
+		assert_eq!(Some(Entry::Option(t(), o())), entry("/packs/basic/textures/stone/plain/option.wiwimeta"));
+		assert_eq!(Some(Entry::Provider(t(), o(), p())), entry("/packs/basic/textures/stone/plain/all"));
+		assert_eq!(
+			Some(Entry::Provider(t(), o(), p())),
+			entry("/packs/basic/textures/stone/plain/all/files/stone.png")
+		);
+		assert_eq!(None, entry("/packs/basic-two/pack.wiwimeta"));
+		assert_eq!(None, entry("/packs/basic/../addon/pack.wiwimeta"));
+	}
+
+	#[test]
+	fn outermost_entries_are_kept() {
+		let t = |t: &str| texture2::nr::ID::new(t.into());
+		let o = |o: &str| option2::nr::ID::new(o.into());
+
+		let result = outermost(vec![
+			changed("basic", Entry::Option(t("stone"), o("plain"))),
+			changed("addon", Entry::Texture(t("stone"))),
+			changed("basic", Entry::Texture(t("stone"))),
+			changed("basic", Entry::Option(t("dirt"), o("handmade"))),
+			changed("basic", Entry::Option(t("dirt"), o("handmade")))
+		]);
+		assert_eq!(vec![
+			changed("addon", Entry::Texture(t("stone"))),
+			changed("basic", Entry::Texture(t("stone"))),
+			changed("basic", Entry::Option(t("dirt"), o("handmade")))
+		], result);
+
+		let result = outermost(vec![
+			changed("basic", Entry::Texture(t("stone"))),
+			changed("basic", Entry::Pack)
+		]);
+		assert_eq!(vec![changed("basic", Entry::Pack)], result);
+	}
+
+	#[test]
+	fn changes_are_debounced() {
+		let start = Instant::now();
+		let ms = |ms| start + Duration::from_millis(ms);
+		let mut debouncer = Debouncer::new(Duration::from_millis(300));
+
+		assert_eq!(None, debouncer.take(ms(0)));
+		debouncer.push(vec!["b".into()], ms(0));
+		debouncer.push(vec!["a".into(), "b".into()], ms(200));
+		assert_eq!(None, debouncer.take(ms(400)));
+		assert_eq!(Some(vec!["a".into(), "b".into()]), debouncer.take(ms(500)));
+		assert_eq!(None, debouncer.take(ms(1000)));
+	}
+
+	#[tokio::test]
+	async fn watcher_reports_changed_files() {
+		let scratch = test_util::scratch_copy("basic", "watch");
+		let dirs = [scratch.to_str().unwrap().to_string()];
+		let mut watcher = Watcher::new(Duration::from_millis(100)).unwrap();
+		watcher.watch(&dirs).unwrap();
+
+		let manifest = scratch.join("textures/stone/texture.wiwimeta");
+		let added = scratch.join("textures/stone/plain/all/stone.png");
+		let removed = scratch.join("pack.wiwimeta");
+		fs::write(&manifest, fs::read_to_string(&manifest).unwrap() + "\n").unwrap();
+		fs::write(&added, b"png").unwrap();
+		fs::remove_file(&removed).unwrap();
+
+		// a burst of changes comes back together, once it's quiet
+		let changed = timeout(Duration::from_secs(5), watcher.next()).await.unwrap();
+		for path in [&manifest, &added, &removed] {
+			assert!(changed.contains(path), "{path:?} in {changed:?}");
+		}
+
+		// and nothing once it's not watched anymore
+		watcher.watch(&[]).unwrap();
+		fs::write(scratch.join("textures/dirt/texture.wiwimeta"), b"").unwrap();
+		assert!(timeout(Duration::from_millis(500), watcher.next()).await.is_err());
+
+		fs::remove_dir_all(&scratch).unwrap();
+	}
+}
@@ -8,12 +8,70 @@ pub enum Error {
 	#[error("only absolute paths are allowed, provided path is relative:\n{0}")]
 	AbsolutePathOnly(String),
 
+	#[error(
+		"pack \"{pack_id}\" can't be removed, since loaded packs depend on it: {}",
+		.dependents.join(", ")
+	)]
+	DependedOn {
+		pack_id: String,
+		dependents: Vec<String>
+	},
+
+	#[error(transparent)]
+	DependencyCycle(#[from] crate::error::dependency_err::Cycle),
+
 	#[error("pack with duplicate id of \"{0}\"")]
 	DuplicateID(String),
 
+	#[error(
+		"pack \"{pack_id}\" ({}) can't be loaded, since loaded pack \"{loaded_pack_id}\" is incompatible with {req}",
+		.version.as_ref().map(|v| v.to_string()).unwrap_or_else(|| "no version".into())
+	)]
+	IncompatibleWithLoaded {
+		pack_id: String,
+		version: Option<::semver::Version>,
+		loaded_pack_id: String,
+		req: ::semver::VersionReq
+	},
+
+	#[error("invalid selected option ID:\n{0}")]
+	InvalidOptionID(#[from] ::wiwipaccer_common::ParseOptionIDError),
+
+	#[error("pack \"{pack_id}\" can't be added, since loaded pack \"{dependent_id}\" optionally depends on it, and:\n{error}")]
+	OptionalDependent {
+		pack_id: String,
+		dependent_id: String,
+		#[source]
+		error: Box<crate::pack2::Error>
+	},
+
+	#[error("can't move pack to position {index}, workspace only has {len} packs")]
+	PackIndexOutOfRange {
+		index: usize,
+		len: usize
+	},
+
 	#[error(transparent)]
 	PackError(#[from] crate::pack2::Error),
 
+	#[error("pack \"{old_id}\" has changed its id to \"{new_id}\" on disk (remove it and add it again instead)")]
+	ReloadChangedID {
+		old_id: String,
+		new_id: String
+	},
+
+	#[error(
+		"reloaded pack \"{pack_id}\" no longer satisfies loaded packs depending on it: {}",
+		.dependents.join(", ")
+	)]
+	ReloadBreaksDependents {
+		pack_id: String,
+		dependents: Vec<String>
+	},
+
+	#[error("no pack with id \"{0}\" in workspace")]
+	UnknownPackID(String),
+
 	#[error(transparent)]
 	UtilError(#[from] crate::util::error::Error)
 }
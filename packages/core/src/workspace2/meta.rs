@@ -10,26 +10,67 @@ enum WorkspaceMeta {
 	Version1 {
 		name: nm::Name,
 		packs: nm::Packs
+	},
+	#[serde(rename = "2")]
+	Version2 {
+		name: nm::Name,
+		packs: nm::Packs,
+		selections: nm::Selections,
+		mc_version: nm::MCVersion
+	},
+	#[serde(rename = "3")]
+	Version3 {
+		name: nm::Name,
+		packs: nm::Packs,
+		selections: nm::Selections,
+		mc_version: nm::MCVersion,
+		strictness: nm::Strictness
 	}
 }
 
 pub(super) struct WorkspaceUnversioned {
 	pub(super) name: nm::Name,
-	pub(super) packs: nm::Packs
+	pub(super) packs: nm::Packs,
+	pub(super) selections: nm::Selections,
+	pub(super) mc_version: nm::MCVersion,
+	pub(super) strictness: nm::Strictness
 }
 
+#[inline]
 pub(super) fn deserialise_workspace(s: &str) -> Result<WorkspaceUnversioned> {
+	ron::from_str(s).map(unversion).map_err(Into::into)
+}
+
+fn unversion(meta: WorkspaceMeta) -> WorkspaceUnversioned {
 	use WorkspaceMeta::*;
-	Ok(match ron::from_str(s)? {
+	match meta {
 		Version1 { name, packs } => {
-			WorkspaceUnversioned { name, packs }
+			let selections = nm::Selections::default();
+			let mc_version = nm::MCVersion::default();
+			let strictness = nm::Strictness::default();
+			WorkspaceUnversioned { name, packs, selections, mc_version, strictness }
 		}
-	})
+		Version2 { name, packs, selections, mc_version } => {
+			let strictness = nm::Strictness::default();
+			WorkspaceUnversioned { name, packs, selections, mc_version, strictness }
+		}
+		Version3 { name, packs, selections, mc_version, strictness } => {
+			WorkspaceUnversioned { name, packs, selections, mc_version, strictness }
+		}
+	}
+}
+
+/// Upgrades a config to the latest `meta_version`, or `None` if it already is
+/// the latest
+pub(crate) fn upgrade_workspace(s: &str) -> Result<Option<String>> {
+	let meta = ron::from_str(s)?;
+	if let WorkspaceMeta::Version3 { .. } = meta { return Ok(None) }
+	serialise_workspace(unversion(meta)).map(Some)
 }
 
 #[inline]
 pub(super) fn serialise_workspace(workspace: WorkspaceUnversioned) -> Result<String> {
-	let WorkspaceUnversioned { name, packs } = workspace;
-	let workspace = WorkspaceMeta::Version1 { name, packs };
+	let WorkspaceUnversioned { name, packs, selections, mc_version, strictness } = workspace;
+	let workspace = WorkspaceMeta::Version3 { name, packs, selections, mc_version, strictness };
 	ron::to_string_minified(&workspace).map_err(Into::into)
 }
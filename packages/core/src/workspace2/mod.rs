@@ -5,18 +5,32 @@ mod meta;
 mod rt;
 
 pub use self::error::Error;
+pub(crate) use self::meta::upgrade_workspace;
 pub use self::rt::{ WorkspaceRuntime, FrontendData };
-use super::pack2;
+use super::{ build, load, pack2, texture2 };
 use ::hashbrown::HashMap;
+use ::mc_versions::MCVersionRef;
+
+/// pack ID and texture ID of a texture in the workspace
+type TextureKey = (pack2::nr::ID, texture2::nr::ID);
 
 ::nominal::nominal_mod! {
 	mod nm {
 		nominal!(pub Name, inner: String);
 		nominal!(pub Packs, inner: Vec<String>);
+		nominal!(pub Selections, inner: Vec<String>);
+		nominal!(pub MCVersion, inner: Option<String>);
+		nominal!(pub Strictness, inner: load::Strictness);
 	}
 	pub mod nr {
 		nominal!(pub Name, inner: String);
 		nominal!(pub Packs, inner: HashMap<pack2::nr::ID, pack2::PackRuntime>);
 		nominal!(pub PackIDs, inner: Vec<pack2::nr::ID>);
+		nominal!(pub Extensions, inner: HashMap<pack2::nr::ID, HashMap<texture2::nr::ID, Vec<TextureKey>>>);
+		nominal!(pub Selections, inner: build::Selections);
+		nominal!(pub InvalidSelections, inner: Vec<String>);
+		nominal!(pub MCVersion, inner: Option<MCVersionRef>);
+		nominal!(pub InvalidMCVersion, inner: Option<String>);
+		nominal!(pub Strictness, inner: load::Strictness);
 	}
 }
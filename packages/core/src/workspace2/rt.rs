@@ -1,5 +1,10 @@
-use ::mc_versions::MCVersionRef;
+use ::mc_versions::{ MCVersion, MCVersionRef };
+use crate::build::Selections;
+use crate::error::dependency_err;
+use crate::load::Strictness;
 use crate::pack2::{ self, DependencyResult };
+use crate::texture2::{ self, MergedTexture, TextureRuntime };
+use crate::watch;
 use super::{ meta, nm, nr };
 use super::error::*;
 use ::async_trait::async_trait;
@@ -7,11 +12,25 @@ use ::camino::Utf8Path;
 use ::hashbrown::HashMap;
 use ::serde::Serialize;
 use ::std::ffi::OsStr;
+use ::std::path::Path;
+use ::wiwipaccer_common::OptionID;
 
 pub struct WorkspaceRuntime {
 	name: nr::Name,
 	packs: nr::Packs,
-	pack_ids: nr::PackIDs
+	pack_ids: nr::PackIDs,
+	/// textures extending each texture, in workspace pack order. Kept up to
+	/// date whenever packs are added, removed, reloaded or moved
+	extensions: nr::Extensions,
+	selections: nr::Selections,
+	/// saved selections that couldn't be parsed when opening the workspace,
+	/// kept so they're saved again
+	invalid_selections: nr::InvalidSelections,
+	mc_version: nr::MCVersion,
+	/// saved minecraft version that isn't known, kept so it's saved again
+	/// until another version is set
+	invalid_mc_version: nr::InvalidMCVersion,
+	strictness: nr::Strictness
 }
 
 impl WorkspaceRuntime {
@@ -19,19 +38,56 @@ impl WorkspaceRuntime {
 	pub fn new(name: nr::Name) -> Self {
 		let packs = nr::Packs::default();
 		let pack_ids = nr::PackIDs::default();
-		Self { name, packs, pack_ids }
+		let extensions = nr::Extensions::default();
+		let selections = nr::Selections::default();
+		let invalid_selections = nr::InvalidSelections::default();
+		let mc_version = nr::MCVersion::default();
+		let invalid_mc_version = nr::InvalidMCVersion::default();
+		let strictness = nr::Strictness::default();
+		Self {
+			name,
+			packs,
+			pack_ids,
+			extensions,
+			selections,
+			invalid_selections,
+			mc_version,
+			invalid_mc_version,
+			strictness
+		}
 	}
 
 	pub async fn from_config_str(config: &str) -> Result<Self> {
 		let meta::WorkspaceUnversioned {
 			name,
-			packs
+			packs,
+			selections,
+			mc_version,
+			strictness
 		} = meta::deserialise_workspace(config)?;
 
 		let mut new = Self::new(nr::Name::new(name.into_inner()));
+		new.strictness = strictness.transmute_nom();
+
+		new.add_packs(packs.ref_inner()).await?;
 
-		for dir in packs.into_inner() {
-			new.add_pack(&dir).await?;
+		// selections aren't checked against the loaded packs here, since a pack
+		// can be missing or changed since the config was saved. they get checked
+		// when building. ones that can't even be parsed are set aside (and saved
+		// again as they were), so one bad entry doesn't stop the workspace
+		// from opening. same for an unknown minecraft version
+		for option_id in selections.into_inner() {
+			match option_id.parse() {
+				Ok(parsed) => { new.selections.mut_inner().select(parsed); }
+				Err(_) => { new.invalid_selections.mut_inner().push(option_id) }
+			}
+		}
+
+		if let Some(mc_version) = mc_version.into_inner() {
+			match MCVersion::get(&mc_version) {
+				Ok(v) => { new.mc_version = nr::MCVersion::new(Some(v)) }
+				Err(_) => { new.invalid_mc_version = nr::InvalidMCVersion::new(Some(mc_version)) }
+			}
 		}
 
 		Ok(new)
@@ -39,50 +95,551 @@ impl WorkspaceRuntime {
 
 	pub fn to_config_str(&self) -> Result<String> {
 		let name = self.name.clone().transmute_nom();
-		let packs = self.packs.ref_inner()
-			.values()
-			.map(|p| p.dir().ref_inner())
+		let packs = self.packs()
+			.map(|p| p.added_dir().ref_inner())
 			.cloned()
 			.collect();
 		let packs = nm::Packs::new(packs);
+		let selections = self.selections.ref_inner()
+			.iter()
+			.map(OptionID::to_string)
+			.chain(self.invalid_selections.ref_inner().iter().cloned())
+			.collect();
+		let selections = nm::Selections::new(selections);
+		let mc_version = self.mc_version.ref_inner()
+			.map(|v| v.name.into())
+			.or_else(|| self.invalid_mc_version.ref_inner().clone());
+		let mc_version = nm::MCVersion::new(mc_version);
+		let strictness = self.strictness.transmute_nom();
 
-		meta::serialise_workspace(meta::WorkspaceUnversioned { name, packs })
+		meta::serialise_workspace(meta::WorkspaceUnversioned {
+			name,
+			packs,
+			selections,
+			mc_version,
+			strictness
+		})
 	}
 
 	pub async fn add_pack(&mut self, dir: &str) -> Result<()> {
-		let resolver = self.dependency_resolver();
-		let pack = pack2::PackRuntime::new(dir, resolver).await?;
-		self.add_pack_common(pack).await
+		let resolver = self.dependency_resolver(&[]);
+		let pack = pack2::PackRuntime::new(dir, resolver, *self.strictness.ref_inner()).await?;
+		self.check_new_pack(&pack, &[])?;
+		self.insert_packs([pack]);
+		Ok(())
+	}
+
+	/// Adds several packs at once. They're loaded in dependency order (so
+	/// they can be provided in any order, as long as all their dependencies
+	/// are either in `dirs` or already in the workspace), but are still
+	/// layered in the order they are provided in. If any of them fail to load,
+	/// none of them are added.
+	pub async fn add_packs(&mut self, dirs: &[String]) -> Result<()> {
+		let mut packs = Vec::with_capacity(dirs.len());
+		for dir in dirs {
+			packs.push(pack2::PackRuntime::read_id_and_dependencies(dir).await?);
+		}
+
+		// loaded here first, and only added to the workspace once they all load
+		let mut order = Vec::with_capacity(dirs.len());
+		let mut staged = Vec::with_capacity(dirs.len());
+		for i in load_order(&packs)? {
+			let resolver = self.dependency_resolver(&staged);
+			let pack = pack2::PackRuntime::new(&dirs[i], resolver, *self.strictness.ref_inner()).await?;
+			self.check_new_pack(&pack, &staged)?;
+
+			order.push(i);
+			staged.push(pack);
+		}
+
+		let mut staged = order.into_iter().zip(staged).collect::<Vec<_>>();
+		staged.sort_unstable_by_key(|(i, _)| *i);
+		self.insert_packs(staged.into_iter().map(|(_, pack)| pack));
+
+		Ok(())
 	}
 
 	pub async fn add_pack_with_dir_osstr(&mut self, dir: &OsStr) -> Result<()> {
-		let resolver = self.dependency_resolver();
-		let pack = pack2::PackRuntime::new_with_osstr(dir, resolver).await?;
-		self.add_pack_common(pack).await
+		let resolver = self.dependency_resolver(&[]);
+		let pack = pack2::PackRuntime::new_with_osstr(dir, resolver, *self.strictness.ref_inner()).await?;
+		self.check_new_pack(&pack, &[])?;
+		self.insert_packs([pack]);
+		Ok(())
+	}
+
+	/// Removes a pack from the workspace, returning it, and clears the
+	/// selections for its textures. This is refused if any other loaded pack
+	/// depends on it, including optionally (since textures in those packs can
+	/// extend textures in this one).
+	pub fn remove_pack(&mut self, pack_id: &str) -> Result<pack2::PackRuntime> {
+		let id = self.loaded_pack_id(pack_id)?;
+
+		let dependents = self.dependents(&id)
+			.map(|p| p.id().ref_inner().clone())
+			.collect::<Vec<_>>();
+		if !dependents.is_empty() {
+			return Err(Error::DependedOn { pack_id: id.into_inner(), dependents })
+		}
+
+		// selections for its textures would fail every build after this
+		self.selections.mut_inner().deselect_pack(id.ref_inner());
+		self.pack_ids.mut_inner().retain(|i| *i != id);
+		let pack = self.packs.mut_inner().remove(&id).expect("invalid state");
+		self.reindex_extensions();
+
+		Ok(pack)
+	}
+
+	/// Reads a pack from disk again, keeping its position in the layering
+	/// order. If it fails to load, or the new one no longer satisfies the
+	/// packs depending on it, the previously loaded pack is kept.
+	pub async fn reload_pack(&mut self, pack_id: &str) -> Result<()> {
+		let id = self.loaded_pack_id(pack_id)?;
+		let dir = self.packs.ref_inner()[&id].added_dir().ref_inner().clone();
+
+		// the old pack is still loaded while loading the new one, but only
+		// dependents could resolve it, and they're checked after
+		let resolver = self.dependency_resolver(&[]);
+		let pack = pack2::PackRuntime::new(&dir, resolver, *self.strictness.ref_inner()).await?;
+
+		if pack.id() != &id {
+			return Err(Error::ReloadChangedID {
+				old_id: id.into_inner(),
+				new_id: pack.id().ref_inner().clone()
+			})
+		}
+
+		self.check_incompatible_with_loaded(&pack, &id, &[])?;
+
+		// it might depend on something that depends on it now
+		let packs = self.packs()
+			.map(|p| if p.id() == &id { &pack } else { p })
+			.map(|p| {
+				let dependencies = p.dependencies().ref_inner()
+					.keys()
+					.chain(p.optional_dependencies().ref_inner().keys())
+					.cloned()
+					.collect();
+				(p.id().clone(), dependencies)
+			})
+			.collect::<Vec<_>>();
+		load_order(&packs)?;
+
+		let dependents = self.dependents(&id)
+			.filter(|p| !still_satisfies(&pack, p))
+			.map(|p| p.id().ref_inner().clone())
+			.collect::<Vec<_>>();
+		if !dependents.is_empty() {
+			return Err(Error::ReloadBreaksDependents { pack_id: id.into_inner(), dependents })
+		}
+
+		self.packs.mut_inner().insert(id, pack);
+		self.reindex_extensions();
+		Ok(())
+	}
+
+	/// Moves a pack to `index` in the layering order (see [`packs`](Self::packs)),
+	/// shifting the packs after it along
+	pub fn move_pack(&mut self, pack_id: &str, index: usize) -> Result<()> {
+		let pack_ids = self.pack_ids.mut_inner();
+
+		let len = pack_ids.len();
+		if index >= len {
+			return Err(Error::PackIndexOutOfRange { index, len })
+		}
+
+		let current = pack_ids.iter()
+			.position(|id| id.ref_inner() == pack_id)
+			.ok_or_else(|| Error::UnknownPackID(pack_id.into()))?;
+
+		let id = pack_ids.remove(current);
+		pack_ids.insert(index, id);
+		self.reindex_extensions();
+
+		Ok(())
+	}
+
+	#[inline]
+	pub fn name(&self) -> &nr::Name {
+		&self.name
+	}
+
+	/// Iterates through packs in the order they were added (which is also the
+	/// order they are layered in when building, with later packs taking priority)
+	#[inline]
+	pub fn packs(&self) -> impl Iterator<Item = &pack2::PackRuntime> {
+		self.pack_ids.ref_inner()
+			.iter()
+			.map(|id| self.packs.ref_inner().get(id).expect("invalid state"))
+	}
+
+	/// Works out which entries in which packs `paths` are in (see
+	/// [`watch::Entry`]), leaving out entries that are in another one that
+	/// changed, since reloading that reloads them too
+	pub fn changed_entries<P: AsRef<Path>>(&self, paths: &[P]) -> Vec<watch::Changed> {
+		let changed = paths.iter()
+			.filter_map(|path| self.packs().find_map(|p| {
+				watch::Entry::for_path(p.dir().ref_inner().as_ref(), path.as_ref())
+					.map(|entry| watch::Changed::new(p.id().clone(), entry))
+			}))
+			.collect();
+		watch::outermost(changed)
+	}
+
+	/// Reloads just the texture, option or provider that changed, or the
+	/// entry it's in when it can't be reloaded on its own (up to the whole
+	/// pack, see [`reload_pack`](Self::reload_pack))
+	pub async fn reload_entry(&mut self, changed: &watch::Changed) -> Result<()> {
+		let id = self.loaded_pack_id(changed.pack_id().ref_inner())?;
+		let strictness = *self.strictness.ref_inner();
+		let mut entry = changed.entry().clone();
+
+		while entry != watch::Entry::Pack {
+			let extended = match &entry {
+				watch::Entry::Texture(texture_id) => {
+					self.extensions.ref_inner()
+						.get(&id)
+						.is_some_and(|e| e.contains_key(texture_id))
+				}
+				_ => { false }
+			};
+
+			let pack = self.packs.mut_inner().get_mut(&id).expect("invalid state");
+			if pack.reload_entry(&entry, strictness, extended).await? { return Ok(()) }
+			entry = entry.parent().expect("only packs have no parent");
+		}
+
+		self.reload_pack(id.ref_inner()).await
+	}
+
+	/// Reloads the entries `paths` are in (see [`changed_entries`](Self::changed_entries)),
+	/// for when files in packs change. Entries that fail to reload are left
+	/// as they were.
+	pub async fn reload_changed<P: AsRef<Path>>(&mut self, paths: &[P])
+		-> Vec<(watch::Changed, Result<()>)>
+	{
+		let mut results = Vec::new();
+		for changed in self.changed_entries(paths) {
+			let result = self.reload_entry(&changed).await;
+			results.push((changed, result));
+		}
+		results
+	}
+
+	/// Merges the options of all textures extending `texture` (which is in
+	/// `pack`) into it. See [`texture2::Extends`] for the rules.
+	pub fn merged_texture<'h>(
+		&'h self,
+		pack: &pack2::PackRuntime,
+		texture: &'h TextureRuntime
+	) -> MergedTexture<'h> {
+		let extensions = self.extensions.ref_inner()
+			.get(pack.id())
+			.and_then(|textures| textures.get(texture.id()))
+			.into_iter()
+			.flatten()
+			.map(|(pack_id, texture_id)| {
+				self.packs.ref_inner()[pack_id]
+					.textures()
+					.ref_inner()
+					.get(texture_id)
+					.expect("invalid state")
+			});
+		MergedTexture::new(texture, extensions)
+	}
+
+	#[inline]
+	pub fn selections(&self) -> &nr::Selections {
+		&self.selections
+	}
+
+	/// Saved selections that couldn't be parsed when the workspace was opened
+	/// from a config. They're left out, but saved again as they were.
+	#[inline]
+	pub fn invalid_selections(&self) -> &nr::InvalidSelections {
+		&self.invalid_selections
+	}
+
+	/// Selects an option for the texture it belongs to, replacing the previous
+	/// selection for that texture, if there was one
+	#[inline]
+	pub fn select_option(&mut self, option_id: OptionID) -> Option<OptionID> {
+		self.selections.mut_inner().select(option_id)
+	}
+
+	/// Clears the selection for a texture, so its default option is used
+	/// again, returning the option that was selected, if there was one
+	#[inline]
+	pub fn deselect_option(&mut self, pack_id: &str, texture_id: &str) -> Option<OptionID> {
+		self.selections.mut_inner().deselect(pack_id, texture_id)
+	}
+
+	#[inline]
+	pub fn mc_version(&self) -> &nr::MCVersion {
+		&self.mc_version
+	}
+
+	/// Saved minecraft version that isn't known, from when the workspace was
+	/// opened from a config. It's saved again as it was, until a version is set.
+	#[inline]
+	pub fn invalid_mc_version(&self) -> &nr::InvalidMCVersion {
+		&self.invalid_mc_version
 	}
 
 	#[inline]
-	fn dependency_resolver(&self) -> DependencyResolver {
+	pub fn set_mc_version(&mut self, mc_version: Option<MCVersionRef>) {
+		self.mc_version = nr::MCVersion::new(mc_version);
+		self.invalid_mc_version = nr::InvalidMCVersion::default();
+	}
+
+	#[inline]
+	pub fn strictness(&self) -> &nr::Strictness {
+		&self.strictness
+	}
+
+	/// Sets how recoverable problems are handled when loading packs. This
+	/// only applies to packs added after it's set.
+	#[inline]
+	pub fn set_strictness(&mut self, strictness: Strictness) {
+		self.strictness = nr::Strictness::new(strictness);
+	}
+
+	/// Resolves dependencies from the loaded packs, and `staged`, packs that
+	/// are being added along with the one being loaded
+	#[inline]
+	fn dependency_resolver<'h>(&'h self, staged: &'h [pack2::PackRuntime]) -> DependencyResolver<'h> {
 		let packs = &self.packs;
-		DependencyResolver { packs }
+		DependencyResolver { packs, staged }
+	}
+
+	/// Checks that a newly loaded pack can be added, alongside the loaded packs
+	/// and `staged`, packs that are being added along with it
+	fn check_new_pack(&self, pack: &pack2::PackRuntime, staged: &[pack2::PackRuntime]) -> Result<()> {
+		let id = pack.id();
+
+		if self.packs.ref_inner().contains_key(id) || staged.iter().any(|p| p.id() == id) {
+			return Err(Error::DuplicateID(id.ref_inner().clone()))
+		}
+
+		self.check_incompatible_with_loaded(pack, id, staged)?;
+
+		// packs that optionally depend on it can now activate textures extending it
+		for dependent in self.packs().chain(staged) {
+			dependent.check_late_dependency(id, Dependency { pack })
+				.map_err(|error| Error::OptionalDependent {
+					pack_id: id.ref_inner().clone(),
+					dependent_id: dependent.id().ref_inner().clone(),
+					error: Box::new(error)
+				})?;
+		}
+
+		Ok(())
 	}
 
-	async fn add_pack_common(&mut self, pack: pack2::PackRuntime) -> Result<()> {
-		let id = pack.id().clone();
+	/// Adds packs that have been checked with [`check_new_pack`](Self::check_new_pack),
+	/// layering them after the loaded packs, in order
+	fn insert_packs<I: IntoIterator<Item = pack2::PackRuntime>>(&mut self, packs: I) {
+		for pack in packs {
+			let id = pack.id().clone();
+			self.packs.mut_inner().insert(id.clone(), pack);
+			self.pack_ids.mut_inner().push(id);
+		}
 
-		if self.packs.ref_inner().contains_key(&id) {
-			return Err(Error::DuplicateID(id.into_inner()))
+		let waiting = self.packs()
+			.flat_map(|p| p.waiting_on().map(move |d| (p.id(), d)))
+			.filter(|(_, d)| self.packs.ref_inner().contains_key(*d))
+			.map(|(p, d)| (p.clone(), d.clone()))
+			.collect::<Vec<_>>();
+		for (pack_id, dependency_id) in waiting {
+			self.packs.mut_inner()
+				.get_mut(&pack_id)
+				.expect("invalid state")
+				.activate_extending(&dependency_id);
 		}
 
-		self.packs.mut_inner().insert(id.clone(), pack);
-		self.pack_ids.mut_inner().push(id);
+		self.reindex_extensions();
+	}
+
+	/// The new pack checks its own incompatibilities when it's loaded, but packs
+	/// already loaded (or staged) can also be incompatible with the new one.
+	/// `replacing` is skipped, for when a pack is being reloaded.
+	fn check_incompatible_with_loaded(
+		&self,
+		pack: &pack2::PackRuntime,
+		replacing: &pack2::nr::ID,
+		staged: &[pack2::PackRuntime]
+	) -> Result<()> {
+		let id = pack.id();
+
+		for loaded in self.packs().chain(staged).filter(|p| p.id() != replacing) {
+			let req = match loaded.incompatible_with().ref_inner().get(id) {
+				Some(req) => { req }
+				None => { continue }
+			};
+
+			let version = pack.version().ref_inner();
+			if version.as_ref().map(|v| req.matches(v)).unwrap_or(true) {
+				return Err(Error::IncompatibleWithLoaded {
+					pack_id: id.ref_inner().clone(),
+					version: version.clone(),
+					loaded_pack_id: loaded.id().ref_inner().clone(),
+					req: req.clone()
+				})
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Rebuilds the index of which textures extend which, so merging doesn't
+	/// have to look through every texture in every pack each time
+	fn reindex_extensions(&mut self) {
+		let mut extensions = nr::Extensions::default();
+		let index = extensions.mut_inner();
+
+		for pack in self.packs() {
+			for texture in pack.textures().ref_inner().values() {
+				let extends = match texture.extends().ref_inner() {
+					Some(e) => { e }
+					None => { continue }
+				};
+
+				index.entry(extends.pack_id().clone())
+					.or_default()
+					.entry(extends.texture_id().clone())
+					.or_default()
+					.push((pack.id().clone(), texture.id().clone()));
+			}
+		}
+
+		self.extensions = extensions;
+	}
+
+	fn loaded_pack_id(&self, pack_id: &str) -> Result<pack2::nr::ID> {
+		let id = pack2::nr::ID::new(pack_id.into());
+		match self.packs.ref_inner().contains_key(&id) {
+			true => { Ok(id) }
+			false => { Err(Error::UnknownPackID(id.into_inner())) }
+		}
+	}
+
+	/// Loaded packs that depend on `id`, including optionally
+	fn dependents<'h>(
+		&'h self,
+		id: &'h pack2::nr::ID
+	) -> impl Iterator<Item = &'h pack2::PackRuntime> {
+		self.packs().filter(move |p| {
+			p.dependencies().ref_inner().contains_key(id)
+				|| p.optional_dependencies().ref_inner().contains_key(id)
+		})
+	}
+}
+
+/// Checks if a reloaded `pack` still satisfies the version requirement of
+/// `dependent`, and still has all the textures `dependent` extends
+fn still_satisfies(pack: &pack2::PackRuntime, dependent: &pack2::PackRuntime) -> bool {
+	let req = dependent.dependencies().ref_inner()
+		.get(pack.id())
+		.or_else(|| dependent.optional_dependencies().ref_inner().get(pack.id()));
+	let version_ok = match (req, pack.version().ref_inner()) {
+		(Some(req), Some(v)) => { req.matches(v) }
+		_ => { true }
+	};
+
+	let textures_ok = dependent.textures().ref_inner()
+		.values()
+		.filter_map(|t| t.extends().ref_inner().as_ref())
+		.filter(|e| e.pack_id() == pack.id())
+		.all(|e| match pack.textures().ref_inner().get(e.texture_id()) {
+			Some(t) => { t.extends().ref_inner().is_none() }
+			None => { false }
+		});
+
+	version_ok && textures_ok
+}
+
+/// Works out an order to load packs in, so that every pack is loaded after
+/// its dependencies, returning indices into `packs`. Dependencies that aren't
+/// in `packs` are skipped, since they're either already loaded, or missing
+/// (which is reported when loading the pack that depends on it).
+fn load_order(packs: &[(pack2::nr::ID, Vec<pack2::nr::ID>)]) -> Result<Vec<usize>> {
+	let mut indices = HashMap::with_capacity(packs.len());
+	for (i, (id, _)) in packs.iter().enumerate() {
+		if indices.insert(id, i).is_some() {
+			return Err(Error::DuplicateID(id.ref_inner().clone()))
+		}
+	}
+
+	let mut load_order = LoadOrder {
+		packs,
+		indices,
+		states: vec![VisitState::NotVisited; packs.len()],
+		stack: Vec::new(),
+		order: Vec::with_capacity(packs.len())
+	};
+
+	for i in 0..packs.len() {
+		load_order.visit(i)?;
+	}
+
+	Ok(load_order.order)
+}
+
+#[derive(Clone, Copy)]
+enum VisitState {
+	NotVisited,
+	Visiting,
+	Visited
+}
+
+struct LoadOrder<'h> {
+	packs: &'h [(pack2::nr::ID, Vec<pack2::nr::ID>)],
+	indices: HashMap<&'h pack2::nr::ID, usize>,
+	states: Vec<VisitState>,
+	/// packs currently being visited, for reporting cycles
+	stack: Vec<usize>,
+	order: Vec<usize>
+}
+
+impl<'h> LoadOrder<'h> {
+	/// depth first, adding a pack to the order after all its dependencies
+	fn visit(&mut self, i: usize) -> Result<()> {
+		match self.states[i] {
+			VisitState::Visited => { return Ok(()) }
+			VisitState::Visiting => {
+				let start = self.stack.iter()
+					.position(|s| *s == i)
+					.expect("invalid state");
+				let path = self.stack[start..]
+					.iter()
+					.chain([i].iter())
+					.map(|i| self.packs[*i].0.ref_inner().clone())
+					.collect();
+				return Err(dependency_err::cycle(path).into())
+			}
+			VisitState::NotVisited => {}
+		}
+
+		self.states[i] = VisitState::Visiting;
+		self.stack.push(i);
+
+		for dependency in &self.packs[i].1 {
+			if let Some(d) = self.indices.get(dependency) {
+				self.visit(*d)?;
+			}
+		}
+
+		self.stack.pop();
+		self.states[i] = VisitState::Visited;
+		self.order.push(i);
 
 		Ok(())
 	}
 }
 
 pub struct DependencyResolver<'h> {
-	packs: &'h nr::Packs
+	packs: &'h nr::Packs,
+	staged: &'h [pack2::PackRuntime]
 }
 
 pub struct Dependency<'h> {
@@ -98,7 +655,10 @@ impl<'h> pack2::DependencyResolver for DependencyResolver<'h> {
 		pack_id: &pack2::nr::ID,
 		version_req: &::semver::VersionReq
 	) -> Result<DependencyResult<Self::Dependency>, Box<dyn std::error::Error + Send>> {
-		let pack = match self.packs.ref_inner().get(pack_id) {
+		let pack = self.packs.ref_inner()
+			.get(pack_id)
+			.or_else(|| self.staged.iter().find(|p| p.id() == pack_id));
+		let pack = match pack {
 			Some(s) => { s }
 			None => { return Ok(DependencyResult::NotFound) }
 		};
@@ -115,23 +675,545 @@ impl<'h> pack2::DependencyResolver for DependencyResolver<'h> {
 }
 
 #[async_trait]
-impl<'h> pack2::Dependency for Dependency<'h> {}
+impl<'h> pack2::Dependency for Dependency<'h> {
+	#[inline]
+	fn version(&self) -> Option<&::semver::Version> {
+		self.pack.version().ref_inner().as_ref()
+	}
+
+	#[inline]
+	fn texture(&self, texture_id: &texture2::nr::ID) -> Option<&TextureRuntime> {
+		self.pack.textures().ref_inner().get(texture_id)
+	}
+}
 
 #[derive(Serialize)]
 pub struct FrontendData<'h> {
 	name: &'h nr::Name,
-	packs: Vec<pack2::FrontendData<'h>>
+	packs: Vec<pack2::FrontendData<'h>>,
+	selections: Vec<String>
 }
 
 impl<'h> FrontendData<'h> {
-	pub fn new(workspace: &'h WorkspaceRuntime, mc_version: MCVersionRef) -> Self {
+	/// Names and descriptions are picked for `locales`, the user's preferred
+	/// locales, most preferred first (see [`Localised::get`](crate::locale::Localised::get))
+	pub fn new(workspace: &'h WorkspaceRuntime, mc_version: MCVersionRef, locales: &[String]) -> Self {
 		let name = &workspace.name;
 		let packs = workspace.pack_ids.ref_inner()
 			.iter()
 			.map(|id| workspace.packs.ref_inner().get(id).expect("invalid state"))
-			.map(|p| pack2::FrontendData::new(p, mc_version))
+			.map(|p| pack2::FrontendData::new(p, mc_version, locales, |t| workspace.merged_texture(p, t)))
 			.collect();
+		let selections = workspace.selections.ref_inner()
+			.iter()
+			.map(OptionID::to_string)
+			.collect();
+
+		Self { name, packs, selections }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::error::load_err::Severity;
+	use crate::test_util::{ self, fixture };
+
+	#[tokio::test]
+	async fn config_roundtrip() {
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.select_option("lt:stone:random".parse().unwrap());
+		workspace.select_option("lt:dirt:plain".parse().unwrap());
+		workspace.set_mc_version(Some(MCVersion::get("1.20.4").unwrap()));
+		workspace.set_strictness(Strictness::Lenient);
+
+		let config = workspace.to_config_str().unwrap();
+		let workspace = WorkspaceRuntime::from_config_str(&config).await.unwrap();
+
+		let selections = workspace.selections().ref_inner();
+		assert_eq!("random", selections.get("lt", "stone").unwrap().option_id_ref());
+		assert_eq!("plain", selections.get("lt", "dirt").unwrap().option_id_ref());
+		assert_eq!("1.20.4", workspace.mc_version().ref_inner().unwrap().name);
+		assert_eq!(Strictness::Lenient, *workspace.strictness().ref_inner());
+		assert_eq!(config, workspace.to_config_str().unwrap());
+	}
+
+	#[tokio::test]
+	async fn invalid_selections_are_kept_aside() {
+		let config = r#"(meta_version:"3",name:"uwu",packs:[],selections:["lt:stone:random","owo","lt:dirt"],mc_version:"1.0.0.0",strictness:"strict")"#;
+		let mut workspace = WorkspaceRuntime::from_config_str(config).await.unwrap();
+
+		let selections = workspace.selections().ref_inner();
+		assert_eq!(1, selections.iter().count());
+		assert_eq!("random", selections.get("lt", "stone").unwrap().option_id_ref());
+		assert_eq!(&["owo", "lt:dirt"], &**workspace.invalid_selections().ref_inner());
+		assert!(workspace.mc_version().ref_inner().is_none());
+		assert_eq!(Some("1.0.0.0"), workspace.invalid_mc_version().ref_inner().as_deref());
+
+		// saved again as they were
+		assert_eq!(config, workspace.to_config_str().unwrap());
+
+		// until another version is picked
+		workspace.set_mc_version(Some(MCVersion::get("1.20.4").unwrap()));
+		assert!(workspace.invalid_mc_version().ref_inner().is_none());
+		assert!(workspace.to_config_str().unwrap().contains(r#"mc_version:"1.20.4""#));
+	}
+
+	#[tokio::test]
+	async fn config_version_1_still_loads() {
+		let workspace = WorkspaceRuntime::from_config_str(r#"(meta_version:"1",name:"uwu",packs:[])"#)
+			.await
+			.unwrap();
+
+		assert_eq!("uwu", workspace.name().ref_inner());
+		assert_eq!(0, workspace.selections().ref_inner().iter().count());
+		assert!(workspace.mc_version().ref_inner().is_none());
+	}
+
+	#[tokio::test]
+	async fn config_version_2_is_strict() {
+		let config = r#"(meta_version:"2",name:"uwu",packs:[],selections:[],mc_version:Some("1.20.4"))"#;
+		let workspace = WorkspaceRuntime::from_config_str(config).await.unwrap();
+
+		assert_eq!(Strictness::Strict, *workspace.strictness().ref_inner());
+		assert!(workspace.to_config_str().unwrap().contains(r#"meta_version:"3""#));
+	}
+
+	#[tokio::test]
+	async fn missing_default_depends_on_strictness() {
+		let dir = fixture("broken-default");
+
+		// strict, so the texture fails to load, and so does the pack
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		let error = workspace.add_pack(&dir).await.unwrap_err();
+		let diagnostics = match error {
+			Error::PackError(pack2::Error::LoadFailed(e)) => { e.diagnostics().to_vec() }
+			e => { panic!("unexpected error: {e}") }
+		};
+		assert_eq!(1, diagnostics.len());
+		assert_eq!(Severity::Error, diagnostics[0].severity());
+		assert_eq!(0, workspace.packs().count());
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.set_strictness(Strictness::Lenient);
+		workspace.add_pack(&dir).await.unwrap();
+
+		let pack = workspace.packs().next().unwrap();
+		let diagnostics = pack.diagnostics().ref_inner();
+		assert_eq!(1, diagnostics.len());
+		assert_eq!(Severity::Warning, diagnostics[0].severity());
+		assert!(diagnostics[0].path().ends_with("textures/stone"));
+		assert!(diagnostics[0].message().contains("nonexistent"));
+
+		let texture = pack.textures().ref_inner().values().next().unwrap();
+		assert!(texture.default().ref_inner().is_none());
+	}
+
+	#[tokio::test]
+	async fn broken_parts_of_packs_are_left_out() {
+		let dir = fixture("broken-many");
+
+		// strict mode doesn't load packs with broken parts
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		let error = workspace.add_pack(&dir).await.unwrap_err();
+		assert!(error.to_string().contains("pack failed to load (3 errors)"));
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.set_strictness(Strictness::Lenient);
+		workspace.add_pack(&dir).await.unwrap();
+		let pack = workspace.packs().next().unwrap();
+
+		// every problem is collected at once, not just the first, and in the
+		// same order every time, even though entries are loaded concurrently
+		let paths = pack.diagnostics()
+			.ref_inner()
+			.iter()
+			.inspect(|d| assert_eq!(Severity::Error, d.severity()))
+			.map(|d| d.path().strip_prefix(&dir).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(vec![
+			"/textures/dirt",
+			"/textures/stone/bad",
+			"/textures/stone/plain/all"
+		], paths);
+
+		// and recorded on the texture / option they were in
+		let texture_ids = pack.textures().ref_inner().keys().map(|t| t.ref_inner().as_str()).collect::<Vec<_>>();
+		assert_eq!(vec!["stone"], texture_ids);
+		let stone = pack.textures().ref_inner().values().next().unwrap();
+		let merged = workspace.merged_texture(pack, stone);
+		assert_eq!(1, merged.errors().len());
+		assert!(merged.errors()[0].path().ends_with("textures/stone/bad"));
+		let plain = stone.options().ref_inner().values().next().unwrap();
+		assert_eq!(1, plain.errors().ref_inner().len());
+
+		let mc_version = test_util::mc_version("1.20.4");
+		let frontend_data = ::serde_json::to_value(FrontendData::new(&workspace, mc_version, &[])).unwrap();
+		let stone = &frontend_data["packs"][0]["textures"]["stone"];
+		assert_eq!(1, stone["errors"].as_array().unwrap().len());
+		assert_eq!("error", stone["errors"][0]["severity"]);
+		assert_eq!(1, stone["options"]["plain"]["errors"].as_array().unwrap().len());
+	}
+
+	#[tokio::test]
+	async fn missing_dirs_are_recorded() {
+		let scratch = test_util::scratch_copy("basic", "missing-dirs");
+		let dir = scratch.to_str().unwrap();
+		::std::fs::remove_dir_all(scratch.join("textures")).unwrap();
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		assert!(workspace.add_pack(dir).await.is_err());
+
+		workspace.set_strictness(Strictness::Lenient);
+		workspace.add_pack(dir).await.unwrap();
+		let pack = workspace.packs().next().unwrap();
+		assert!(pack.textures().ref_inner().is_empty());
+		let diagnostics = pack.diagnostics().ref_inner();
+		assert_eq!(1, diagnostics.len());
+		assert_eq!(Severity::Error, diagnostics[0].severity());
+		assert!(diagnostics[0].path().ends_with("/textures"));
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn non_utf8_entries_are_skipped() {
+		use ::std::ffi::OsStr;
+		use ::std::os::unix::ffi::OsStrExt as _;
+
+		let scratch = test_util::scratch_copy("basic", "non-utf8-entries");
+		let dir = scratch.to_str().unwrap();
+		let textures = scratch.join("textures");
+		::std::fs::create_dir(textures.join(OsStr::from_bytes(b"st\xffne"))).unwrap();
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		assert!(workspace.add_pack(dir).await.is_err());
+
+		workspace.set_strictness(Strictness::Lenient);
+		workspace.add_pack(dir).await.unwrap();
+		let pack = workspace.packs().next().unwrap();
+		assert!(pack.textures().ref_inner().contains_key(&texture2::nr::ID::new("stone".into())));
+		let diagnostics = pack.diagnostics().ref_inner();
+		assert_eq!(1, diagnostics.len());
+		assert!(diagnostics[0].path().starts_with(textures.to_str().unwrap()));
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn names_are_picked_for_locales() {
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[test_util::fixture("basic"), test_util::fixture("addon")]).await.unwrap();
+		let mc_version = test_util::mc_version("1.20.4");
+		let frontend_data = |locales: &[&str]| {
+			let locales = locales.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+			::serde_json::to_value(FrontendData::new(&workspace, mc_version, &locales)).unwrap()
+		};
+
+		let data = frontend_data(&["de-AT", "en"]);
+		let addon = &data["packs"][1];
+		assert_eq!("Erweiterung", addon["name"]);
+		assert_eq!("Packerl, das Texturen im Basispaket erweitert", addon["description"]);
+		assert_eq!("zusätzlich", data["packs"][0]["textures"]["stone"]["options"]["extra"]["name"]);
+		// plain strings are the same in every locale
+		assert_eq!("Basic", data["packs"][0]["name"]);
+
+		let data = frontend_data(&["fr"]);
+		assert_eq!("Addon", data["packs"][1]["name"]);
+		assert_eq!("pack that extends textures in the basic pack", data["packs"][1]["description"]);
+	}
+
+	fn ids(packs: &[(&str, &[&str])]) -> Vec<(pack2::nr::ID, Vec<pack2::nr::ID>)> {
+		let id = |id: &str| pack2::nr::ID::new(id.into());
+		packs.iter()
+			.map(|(p, deps)| (id(p), deps.iter().map(|d| id(d)).collect()))
+			.collect()
+	}
+
+	#[test]
+	fn load_order_puts_dependencies_first() {
+		let packs = ids(&[
+			("addon", &["base", "lib"]),
+			("base", &["lib", "somewhere-else"]),
+			("lib", &[]),
+			("other", &[])
+		]);
+		assert_eq!(vec![2, 1, 0, 3], load_order(&packs).unwrap());
+	}
+
+	#[test]
+	fn load_order_reports_cycle() {
+		let packs = ids(&[
+			("lib", &[]),
+			("a", &["b"]),
+			("b", &["lib", "c"]),
+			("c", &["a"])
+		]);
+
+		let error = match load_order(&packs) {
+			Err(Error::DependencyCycle(error)) => { error }
+			_ => { panic!("expected a dependency cycle error") }
+		};
+		assert_eq!(
+			"packs depend on each other in a cycle (each pack depends on the one after it)\n   a -> b -> c -> a",
+			::ts_result::NiceErrorMessage::to_error_message(&error)
+		);
+
+		assert!(load_order(&ids(&[("a", &["a"])])).is_err());
+	}
+
+	#[tokio::test]
+	async fn config_packs_load_in_any_order() {
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[fixture("addon"), fixture("basic")]).await.unwrap();
+
+		let config = workspace.to_config_str().unwrap();
+		let workspace = WorkspaceRuntime::from_config_str(&config).await.unwrap();
+
+		let pack_ids = workspace.packs()
+			.map(|p| p.id().ref_inner().as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(vec!["addon", "basic"], pack_ids);
+	}
+
+	#[tokio::test]
+	async fn add_packs_adds_all_or_nothing() {
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[fixture("addon-bad"), fixture("basic")]).await.unwrap_err();
+		assert_eq!(0, workspace.packs().count());
+
+		workspace.add_pack(&fixture("basic")).await.unwrap();
+		workspace.add_packs(&[fixture("addon"), fixture("basic")]).await.unwrap_err();
+		assert_eq!(1, workspace.packs().count());
+	}
+
+	#[tokio::test]
+	async fn optional_dependencies_and_incompatibilities() {
+		let textures = |workspace: &WorkspaceRuntime, pack: &str| {
+			workspace.packs()
+				.find(|p| p.id().ref_inner() == pack)
+				.unwrap()
+				.textures()
+				.ref_inner()
+				.len()
+		};
+
+		// without the optional dependency, the texture extending it isn't active
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_pack(&fixture("optional")).await.unwrap();
+		assert_eq!(0, textures(&workspace, "optional"));
+
+		let has_glow = |workspace: &WorkspaceRuntime| {
+			let basic = workspace.packs().find(|p| p.id().ref_inner() == "basic").unwrap();
+			let stone = basic.textures().ref_inner().values().find(|t| t.id().ref_inner() == "stone").unwrap();
+			workspace.merged_texture(basic, stone).options().keys().any(|o| o.ref_inner() == "glow")
+		};
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[fixture("optional"), fixture("basic")]).await.unwrap();
+		assert_eq!(1, textures(&workspace, "optional"));
+		assert!(has_glow(&workspace));
+
+		// and it's activated when the optional dependency is added later
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_pack(&fixture("optional")).await.unwrap();
+		workspace.add_pack(&fixture("basic")).await.unwrap();
+		assert_eq!(1, textures(&workspace, "optional"));
+		assert!(has_glow(&workspace));
+
+		// incompatible, whichever one is loaded first
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[fixture("basic"), fixture("addon")]).await.unwrap();
+		let error = workspace.add_pack(&fixture("optional")).await.unwrap_err();
+		assert_eq!("incompatible with loaded packs: addon * (1.0.0 loaded)", error.to_string());
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[fixture("basic"), fixture("optional")]).await.unwrap();
+		let error = workspace.add_pack(&fixture("addon")).await.unwrap_err();
+		assert!(matches!(error, Error::IncompatibleWithLoaded { .. }));
+	}
+
+	#[tokio::test]
+	async fn remove_and_move_packs() {
+		let pack_ids = |workspace: &WorkspaceRuntime| {
+			workspace.packs()
+				.map(|p| p.id().ref_inner().clone())
+				.collect::<Vec<_>>()
+		};
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[fixture("basic"), fixture("addon")]).await.unwrap();
+
+		let error = workspace.remove_pack("basic").map(|_| ()).unwrap_err();
+		assert_eq!("pack \"basic\" can't be removed, since loaded packs depend on it: addon", error.to_string());
+		assert!(matches!(workspace.remove_pack("nope"), Err(Error::UnknownPackID(_))));
+
+		workspace.move_pack("addon", 0).unwrap();
+		assert_eq!(vec!["addon", "basic"], pack_ids(&workspace));
+		assert!(matches!(workspace.move_pack("addon", 2), Err(Error::PackIndexOutOfRange { .. })));
+		assert!(matches!(workspace.move_pack("nope", 0), Err(Error::UnknownPackID(_))));
+
+		let changed = [
+			format!("{}/textures/stone/texture.wiwimeta", fixture("basic")),
+			format!("{}-not-really/pack.wiwimeta", fixture("addon"))
+		];
+		let texture_id = texture2::nr::ID::new("stone".into());
+		let expected = watch::Changed::new(
+			pack2::nr::ID::new("basic".into()),
+			watch::Entry::Texture(texture_id)
+		);
+		assert_eq!(vec![expected], workspace.changed_entries(&changed));
+
+		let has_extra = |workspace: &WorkspaceRuntime| {
+			let basic = workspace.packs().find(|p| p.id().ref_inner() == "basic").unwrap();
+			let stone = basic.textures().ref_inner().values().find(|t| t.id().ref_inner() == "stone").unwrap();
+			workspace.merged_texture(basic, stone).options().keys().any(|o| o.ref_inner() == "extra")
+		};
+		assert!(has_extra(&workspace));
+
+		workspace.remove_pack("addon").unwrap();
+		assert_eq!(vec!["basic"], pack_ids(&workspace));
+		assert!(!has_extra(&workspace));
+		workspace.remove_pack("basic").unwrap();
+		assert_eq!(0, workspace.packs().count());
+	}
+
+	#[tokio::test]
+	async fn removing_a_selected_pack_still_builds() {
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_pack(&fixture("basic")).await.unwrap();
+		workspace.select_option("basic:stone:plain".parse().unwrap());
+		workspace.select_option("basic:dirt:handmade".parse().unwrap());
+		assert_eq!("handmade", workspace.deselect_option("basic", "dirt").unwrap().option_id_ref());
+		assert_eq!(1, workspace.selections().ref_inner().iter().count());
+
+		workspace.remove_pack("basic").unwrap();
+		assert_eq!(0, workspace.selections().ref_inner().iter().count());
+
+		let mc_version = test_util::mc_version("1.20.4");
+		crate::build::build(&workspace, workspace.selections().ref_inner(), mc_version).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn reload_pack() {
+		// basic is copied somewhere it can be changed
+		let scratch = test_util::scratch_copy("basic", "reload");
+		let basic = scratch.to_str().unwrap().to_string();
+		let meta = scratch.join("pack.wiwimeta");
+		let original_meta = ::std::fs::read_to_string(&meta).unwrap();
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[basic.clone(), fixture("addon")]).await.unwrap();
+		workspace.move_pack("basic", 1).unwrap();
+
+		workspace.reload_pack("basic").await.unwrap();
+		let pack_ids = workspace.packs()
+			.map(|p| p.id().ref_inner().as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(vec!["addon", "basic"], pack_ids);
+
+		// addon needs ^1.0.0
+		::std::fs::write(&meta, original_meta.replace("\"1.0.0\"", "\"2.0.0\"")).unwrap();
+		let error = workspace.reload_pack("basic").await.unwrap_err();
+		assert!(matches!(error, Error::ReloadBreaksDependents { .. }));
+		let version = workspace.packs()
+			.find(|p| p.id().ref_inner() == "basic")
+			.and_then(|p| p.version().ref_inner().as_ref())
+			.map(|v| v.to_string());
+		assert_eq!(Some("1.0.0".into()), version);
+
+		::std::fs::write(&meta, original_meta.replace("\"basic\"", "\"basic-two\"")).unwrap();
+		let error = workspace.reload_pack("basic").await.unwrap_err();
+		assert!(matches!(error, Error::ReloadChangedID { .. }));
+
+		::std::fs::remove_dir_all(&scratch).unwrap();
+	}
+
+	#[tokio::test]
+	async fn relative_pack_dirs_match_changed_paths() {
+		// tests run in the crate's dir
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_pack("test/fixtures/packs/../packs/basic").await.unwrap();
+
+		let dir = workspace.packs().next().unwrap().dir().ref_inner().clone();
+		assert_eq!(::std::fs::canonicalize(fixture("basic")).unwrap().to_str().unwrap(), dir);
+
+		let changed = [format!("{dir}/textures/stone/texture.wiwimeta")];
+		let entries = workspace.changed_entries(&changed)
+			.iter()
+			.map(watch::Changed::to_string)
+			.collect::<Vec<_>>();
+		assert_eq!(vec!["basic/stone"], entries);
+
+		// but it's saved as it was added
+		assert!(workspace.to_config_str().unwrap().contains(r#"packs:["test/fixtures/packs/../packs/basic"]"#));
+	}
+
+	#[tokio::test]
+	async fn reload_changed_entries() {
+		let scratch = test_util::scratch_copy("basic", "reload-changed");
+		let basic = scratch.to_str().unwrap().to_string();
+		let provider = scratch.join("textures/stone/plain/all/provider.wiwimeta");
+		let original_provider = ::std::fs::read_to_string(&provider).unwrap();
+
+		let mut workspace = WorkspaceRuntime::new(nr::Name::new("uwu".into()));
+		workspace.add_packs(&[basic, test_util::fixture("addon")]).await.unwrap();
+
+		let stone = |workspace: &WorkspaceRuntime| {
+			let basic = workspace.packs().find(|p| p.id().ref_inner() == "basic").unwrap();
+			let stone = &basic.textures().ref_inner()[&texture2::nr::ID::new("stone".into())];
+			let options = stone.options().ref_inner()
+				.iter()
+				.map(|(id, o)| {
+					let providers = o.providers().ref_inner()
+						.keys()
+						.map(|id| id.ref_inner().clone())
+						.collect::<Vec<String>>();
+					(id.ref_inner().clone(), providers)
+				})
+				.collect::<Vec<_>>();
+			(options, basic.diagnostics().ref_inner().len())
+		};
+
+		// in strict mode, a broken provider isn't reloaded
+		::std::fs::write(&provider, "uwu").unwrap();
+		let results = workspace.reload_changed(&[&provider]).await;
+		assert!(matches!(&*results, [(_, Err(Error::PackError(pack2::Error::LoadFailed(_))))]));
+		let (options, _) = stone(&workspace);
+		assert_eq!(vec![("plain".into(), vec!["all".into()]), ("random".into(), vec!["all".into()])], options);
+
+		// and in lenient mode, it only takes that provider out
+		workspace.set_strictness(Strictness::Lenient);
+		let results = workspace.reload_changed(&[&provider]).await;
+		let entries = results.iter().map(|(c, _)| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(vec!["basic/stone/plain/all"], entries);
+		assert!(results.iter().all(|(_, r)| r.is_ok()));
+		let (options, diagnostics) = stone(&workspace);
+		assert_eq!(vec![("plain".into(), vec![]), ("random".into(), vec!["all".into()])], options);
+		assert_eq!(1, diagnostics);
+
+		::std::fs::write(&provider, &original_provider).unwrap();
+		workspace.reload_changed(&[&provider]).await;
+		let (options, diagnostics) = stone(&workspace);
+		assert_eq!(vec![("plain".into(), vec!["all".into()]), ("random".into(), vec!["all".into()])], options);
+		assert_eq!(0, diagnostics);
+
+		// removing an option that isn't the default just takes it out
+		let plain = scratch.join("textures/stone/plain");
+		::std::fs::remove_dir_all(&plain).unwrap();
+		let changed = [plain.join("option.wiwimeta"), plain.join("all/provider.wiwimeta")];
+		let results = workspace.reload_changed(&changed).await;
+		let entries = results.iter().map(|(c, _)| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(vec!["basic/stone/plain"], entries);
+		let (options, _) = stone(&workspace);
+		assert_eq!(vec![("random".into(), vec!["all".into()])], options);
+
+		// changing a pack manifest reloads the whole pack
+		let results = workspace.reload_changed(&[scratch.join("pack.wiwimeta")]).await;
+		let entries = results.iter().map(|(c, _)| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(vec!["basic"], entries);
 
-		Self { name, packs }
+		::std::fs::remove_dir_all(&scratch).unwrap();
 	}
 }
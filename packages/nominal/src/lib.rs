@@ -10,6 +10,12 @@
 
 #![allow(clippy::partialeq_ne_impl)]
 
+#[cfg(feature = "schemars")]
+use ::schemars::{
+	gen::SchemaGenerator,
+	schema::Schema,
+	JsonSchema
+};
 #[cfg(feature = "serde")]
 use ::serde::{
 	de::{ Deserialize, Deserializer },
@@ -344,3 +350,30 @@ where
 		<T as Serialize>::serialize::<S>(self.ref_inner(), serializer)
 	}
 }
+
+// schema is the same as T's, since serialising is too
+#[cfg(feature = "schemars")]
+impl<T, M> JsonSchema for Nominal<T, M>
+where
+	T: JsonSchema
+{
+	#[inline]
+	fn is_referenceable() -> bool {
+		<T as JsonSchema>::is_referenceable()
+	}
+
+	#[inline]
+	fn schema_name() -> String {
+		<T as JsonSchema>::schema_name()
+	}
+
+	#[inline]
+	fn schema_id() -> ::std::borrow::Cow<'static, str> {
+		<T as JsonSchema>::schema_id()
+	}
+
+	#[inline]
+	fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+		<T as JsonSchema>::json_schema(gen)
+	}
+}